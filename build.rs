@@ -0,0 +1,127 @@
+//! Generates the static key tables from `data/keycodes.json`
+//!
+//! The data is validated with the same rules the server applies to override
+//! files, so invalid key data fails the build instead of the server start.
+
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+#[allow(dead_code)]
+#[path = "src/keycode/types.rs"]
+mod types;
+
+#[allow(dead_code)]
+#[path = "src/keycode/schema.rs"]
+mod schema;
+
+#[allow(dead_code)]
+#[path = "src/keycode/phf.rs"]
+mod phf;
+
+/// Key data compiled into the binary
+const KEY_DATA: &str = "data/keycodes.json";
+
+fn main() {
+    for path in [KEY_DATA, "src/keycode/types.rs", "src/keycode/schema.rs", "src/keycode/phf.rs"] {
+        println!("cargo:rerun-if-changed={}", path);
+    }
+    
+    let data = fs::read_to_string(KEY_DATA)
+        .unwrap_or_else(|e| panic!("cannot read {}: {}", KEY_DATA, e));
+    let keys = schema::parse_key_data(&data)
+        .unwrap_or_else(|e| panic!("{}: {}", KEY_DATA, e));
+    
+    let out_dir = env::var("OUT_DIR").expect("OUT_DIR is set by cargo");
+    fs::write(Path::new(&out_dir).join("key_tables.rs"), generate(&keys))
+        .expect("cannot write key tables");
+}
+
+/// Rust source for the key records, the by-code order and the lookup index
+fn generate(keys: &[schema::KeyRecord]) -> String {
+    let mut out = format!("// Generated by build.rs from {}; do not edit.\n\n", KEY_DATA);
+    
+    writeln!(out, "pub(super) static KEYS: [KeyCode; {}] = [", keys.len()).unwrap();
+    for key in keys {
+        writeln!(out, "    {},", key_literal(key)).unwrap();
+    }
+    out.push_str("];\n\n");
+    
+    // Stable sort keeps data file order among keys sharing a code
+    let mut by_code: Vec<usize> = (0..keys.len()).collect();
+    by_code.sort_by_key(|&index| keys[index].code);
+    
+    writeln!(out, "pub(super) static KEYS_BY_CODE: [&KeyCode; {}] = [", keys.len()).unwrap();
+    for index in by_code {
+        writeln!(out, "    &KEYS[{}],", index).unwrap();
+    }
+    out.push_str("];\n\n");
+    
+    let entries = schema::lookup_entries(keys);
+    let texts: Vec<&str> = entries.iter().map(|(text, _, _)| text.as_str()).collect();
+    let index = phf::build(&texts);
+    
+    writeln!(out, "pub(super) static INDEX_SEED: u64 = {};\n", index.seed).unwrap();
+    
+    writeln!(out, "pub(super) static DISPLACEMENTS: [(u32, u32); {}] = [", index.displacements.len()).unwrap();
+    for (d1, d2) in &index.displacements {
+        writeln!(out, "    ({}, {}),", d1, d2).unwrap();
+    }
+    out.push_str("];\n\n");
+    
+    writeln!(out, "pub(super) static ENTRIES: [IndexEntry; {}] = [", index.slots.len()).unwrap();
+    for &slot in &index.slots {
+        let (text, key, kind) = &entries[slot];
+        writeln!(
+            out,
+            "    IndexEntry {{ text: {:?}, key: {}, kind: EntryKind::{:?} }},",
+            text, key, kind
+        ).unwrap();
+    }
+    out.push_str("];\n");
+    
+    out
+}
+
+/// A `KeyCode` struct expression for one record
+fn key_literal(key: &schema::KeyRecord) -> String {
+    let layouts: Vec<String> = key.layouts
+        .iter()
+        .map(|layout| format!("Layout::{:?}", layout))
+        .collect();
+    
+    format!(
+        "KeyCode {{ name: {:?}, code: {}, category: KeyCategory::{:?}, aliases: &{:?}, \
+        shifted: {:?}, layouts: &[{}], carbon_constant: {:?}, glyph: {:?}, hid_usage: {:?}, \
+        description: {:?}, printable: {}, provenance: {} }}",
+        key.name,
+        key.code,
+        key.category,
+        key.aliases,
+        key.shifted,
+        layouts.join(", "),
+        key.carbon_constant,
+        key.glyph,
+        key.hid_usage,
+        key.description,
+        key.printable,
+        key.provenance.as_ref().map_or("None".to_string(), provenance_literal),
+    )
+}
+
+/// A `Some(Provenance { .. })` expression for one record
+fn provenance_literal(provenance: &schema::ProvenanceRecord) -> String {
+    format!(
+        "Some(Provenance {{ source: CodeSource::{:?}, verified_by: {}, macos_version: {:?}, \
+        keyboard: {:?}, last_verified: {:?}, confidence: Confidence::{:?} }})",
+        provenance.source,
+        provenance.verified_by.map_or("None".to_string(), |method| {
+            format!("Some(VerificationMethod::{:?})", method)
+        }),
+        provenance.macos_version,
+        provenance.keyboard,
+        provenance.last_verified,
+        provenance.confidence,
+    )
+}
@@ -0,0 +1,158 @@
+use crate::keycode::{KeyCode, KeyCombination, KeySequence, Modifiers};
+
+/// System Events modifier constants, in the order `using {...}` lists them
+///
+/// AppleScript cannot tell left from right, so either side presses the
+/// modifier. Fn and Caps Lock have no constant.
+const MODIFIER_CONSTANTS: [(Modifiers, &str); 4] = [
+    (Modifiers::ANY_COMMAND, "command"),
+    (Modifiers::ANY_SHIFT, "shift"),
+    (Modifiers::ANY_OPTION, "option"),
+    (Modifiers::ANY_CONTROL, "control"),
+];
+
+/// System Events constants of the held modifiers, e.g. `["command", "shift"]`
+pub(crate) fn modifier_constants(modifiers: Modifiers) -> Vec<&'static str> {
+    MODIFIER_CONSTANTS
+        .iter()
+        .filter(|(flag, _)| modifiers.intersects(*flag))
+        .map(|(_, constant)| *constant)
+        .collect()
+}
+
+/// The `using {...}` clause for the held modifiers, or "" when none apply
+fn using_clause(modifiers: Modifiers) -> String {
+    let using: Vec<String> = modifier_constants(modifiers)
+        .into_iter()
+        .map(|constant| format!("{} down", constant))
+        .collect();
+    
+    if using.is_empty() {
+        String::new()
+    } else {
+        format!(" using {{{}}}", using.join(", "))
+    }
+}
+
+impl From<&KeyCode> for KeyCombination {
+    fn from(key: &KeyCode) -> Self {
+        Self { modifiers: Modifiers::empty(), key: key.clone(), shifted_symbol: None, fn_key: None }
+    }
+}
+
+impl KeyCombination {
+    /// The `key code N using {...}` command that presses the combination
+    ///
+    /// System Events holds modifiers through `using`, so their key codes never
+    /// appear: Cmd+A is `key code 0 using {command down}`, not key codes 55 and 0.
+    pub fn applescript_command(&self) -> String {
+        format!("key code {}{}", self.key.code, using_clause(self.modifiers))
+    }
+    
+    /// One-line System Events script pressing the combination
+    pub fn to_applescript(&self) -> String {
+        format!("tell application \"System Events\" to {}", self.applescript_command())
+    }
+    
+    /// Held modifiers the `using` clause cannot express and drops, e.g. `["Fn"]`
+    pub fn applescript_unsupported_modifiers(&self) -> Vec<&'static str> {
+        self.modifiers.intersection(Modifiers::FN | Modifiers::CAPS_LOCK).names()
+    }
+}
+
+impl KeySequence {
+    /// System Events script pressing each chord in turn
+    ///
+    /// The sequence's `delay` is inserted between chords.
+    pub fn to_applescript(&self) -> String {
+        if self.is_single() {
+            return self.chords[0].to_applescript();
+        }
+        
+        let mut lines = vec!["tell application \"System Events\"".to_string()];
+        for (i, chord) in self.chords.iter().enumerate() {
+            if let (Some(seconds), true) = (self.delay, i > 0) {
+                lines.push(format!("    delay {}", seconds));
+            }
+            lines.push(format!("    {}", chord.applescript_command()));
+        }
+        lines.push("end tell".to_string());
+        lines.join("\n")
+    }
+    
+    /// The sequence as one `key code {…}` command, which presses the codes in order
+    ///
+    /// Only possible when every chord holds the same modifiers and there is no
+    /// delay, since `using` applies to the whole list.
+    pub fn to_applescript_list(&self) -> Option<String> {
+        let first = self.chords.first()?;
+        let same_modifiers = self.chords.iter().all(|chord| {
+            modifier_constants(chord.modifiers) == modifier_constants(first.modifiers)
+        });
+        if !same_modifiers || self.delay.is_some_and(|seconds| seconds > 0.0) {
+            return None;
+        }
+        
+        let codes: Vec<String> = self.chords.iter().map(|chord| chord.key.code.to_string()).collect();
+        Some(format!(
+            "tell application \"System Events\" to key code {{{}}}{}",
+            codes.join(", "),
+            using_clause(first.modifiers)
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keycode::{parse_shortcut, parse_shortcut_sequence, KEY_DATABASE};
+    
+    #[test]
+    fn test_combination_applescript() {
+        let combination = parse_shortcut("Cmd+Shift+A").unwrap();
+        assert_eq!(combination.applescript_command(), "key code 0 using {command down, shift down}");
+        assert_eq!(
+            combination.to_applescript(),
+            "tell application \"System Events\" to key code 0 using {command down, shift down}"
+        );
+        
+        // Sides press the same modifier
+        let combination = parse_shortcut("Right Option+Left Ctrl+F5").unwrap();
+        assert_eq!(combination.applescript_command(), "key code 96 using {option down, control down}");
+        
+        let escape = KeyCombination::from(KEY_DATABASE.lookup("Escape").unwrap());
+        assert_eq!(escape.applescript_command(), "key code 53");
+    }
+    
+    #[test]
+    fn test_unsupported_modifiers() {
+        let combination = parse_shortcut("Fn+F1").unwrap();
+        assert_eq!(combination.applescript_command(), "key code 122");
+        assert_eq!(combination.applescript_unsupported_modifiers(), vec!["Fn"]);
+        
+        let combination = parse_shortcut("Cmd+CapsLock+A").unwrap();
+        assert_eq!(combination.applescript_unsupported_modifiers(), vec!["CapsLock"]);
+        assert!(parse_shortcut("Cmd+A").unwrap().applescript_unsupported_modifiers().is_empty());
+    }
+    
+    #[test]
+    fn test_sequence_applescript() {
+        let sequence = parse_shortcut_sequence("Ctrl+X Ctrl+S").unwrap();
+        assert_eq!(
+            sequence.to_applescript(),
+            "tell application \"System Events\"\n    key code 7 using {control down}\n    key code 1 using {control down}\nend tell"
+        );
+        assert_eq!(
+            sequence.to_applescript_list().unwrap(),
+            "tell application \"System Events\" to key code {7, 1} using {control down}"
+        );
+        
+        let sequence = KeySequence { delay: Some(0.2), ..sequence };
+        assert!(sequence.to_applescript().contains("control down}\n    delay 0.2\n    key code 1"));
+        assert!(sequence.to_applescript_list().is_none());
+        
+        // Different modifiers per chord need one command each
+        let sequence = parse_shortcut_sequence("Cmd+K S").unwrap();
+        assert!(sequence.to_applescript_list().is_none());
+    }
+}
@@ -0,0 +1,113 @@
+use crate::keycode::{KeyCombination, Modifiers, KEY_DATABASE};
+
+/// Unshifted symbol and whitespace characters on the US layout
+const PLAIN_CHARACTERS: &[(char, &str)] = &[
+    (' ', "Space"),
+    ('\t', "Tab"),
+    ('\n', "Return"),
+    ('\r', "Return"),
+    ('`', "Grave"),
+    ('-', "Minus"),
+    ('=', "Equal"),
+    ('[', "LeftBracket"),
+    (']', "RightBracket"),
+    ('\\', "Backslash"),
+    (';', "Semicolon"),
+    ('\'', "Quote"),
+    (',', "Comma"),
+    ('.', "Period"),
+    ('/', "Slash"),
+];
+
+/// Key name and Shift requirement for an unshifted character or letter
+fn us_layout_key(ch: char) -> Option<(String, bool)> {
+    if ch.is_ascii_lowercase() || ch.is_ascii_digit() {
+        return Some((ch.to_ascii_uppercase().to_string(), false));
+    }
+    if ch.is_ascii_uppercase() {
+        return Some((ch.to_string(), true));
+    }
+    
+    PLAIN_CHARACTERS
+        .iter()
+        .find(|(c, _)| *c == ch)
+        .map(|(_, name)| (name.to_string(), false))
+}
+
+/// Map a character to the keystroke that types it on the US layout
+///
+/// Covers every printable ASCII character plus tab and newline. Shifted
+/// symbols come from the `shifted` field of the key data. Returns `None`
+/// for characters that have no key on the US layout (e.g. 'é', '€').
+pub fn char_to_keystroke(ch: char) -> Option<KeyCombination> {
+    let symbol = ch.to_string();
+    
+    if let Some(key) = KEY_DATABASE.lookup_shifted(&symbol) {
+        return Some(KeyCombination {
+            modifiers: Modifiers::SHIFT,
+            key: key.clone(),
+            shifted_symbol: Some(symbol),
+            fn_key: None,
+        });
+    }
+    
+    let (name, shift) = us_layout_key(ch)?;
+    let key = KEY_DATABASE.lookup(&name)?.clone();
+    
+    let modifiers = if shift { Modifiers::SHIFT } else { Modifiers::empty() };
+    
+    Some(KeyCombination { modifiers, key, shifted_symbol: None, fn_key: None })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_letters_and_digits() {
+        let a = char_to_keystroke('a').unwrap();
+        assert_eq!(a.key.name, "A");
+        assert!(a.modifiers.is_empty());
+        
+        let upper_a = char_to_keystroke('A').unwrap();
+        assert_eq!(upper_a.key.code, 0);
+        assert_eq!(upper_a.modifiers, Modifiers::SHIFT);
+        
+        assert_eq!(char_to_keystroke('7').unwrap().key.code, 26);
+    }
+    
+    #[test]
+    fn test_shifted_symbols() {
+        let question = char_to_keystroke('?').unwrap();
+        assert_eq!(question.key.name, "Slash");
+        assert_eq!(question.to_string(), "Shift+Slash");
+        
+        let at = char_to_keystroke('@').unwrap();
+        assert_eq!(at.key.name, "2");
+        assert_eq!(at.modifiers, Modifiers::SHIFT);
+        
+        assert_eq!(at.shifted_symbol.as_deref(), Some("@"));
+        
+        let slash = char_to_keystroke('/').unwrap();
+        assert!(slash.modifiers.is_empty());
+        assert!(slash.shifted_symbol.is_none());
+    }
+    
+    #[test]
+    fn test_all_printable_ascii_mapped() {
+        for byte in 0x20u8..=0x7E {
+            let ch = byte as char;
+            assert!(char_to_keystroke(ch).is_some(), "no keystroke for {:?}", ch);
+        }
+        
+        assert_eq!(char_to_keystroke('\n').unwrap().key.name, "Return");
+        assert_eq!(char_to_keystroke('\t').unwrap().key.name, "Tab");
+    }
+    
+    #[test]
+    fn test_untypeable_characters() {
+        assert!(char_to_keystroke('é').is_none());
+        assert!(char_to_keystroke('€').is_none());
+        assert!(char_to_keystroke('\u{7}').is_none());
+    }
+}
@@ -1,318 +1,285 @@
-use once_cell::sync::Lazy;
-use std::collections::HashMap;
-use anyhow::Result;
+use once_cell::sync::{Lazy, OnceCell};
+use std::fmt;
+use std::path::PathBuf;
 use tracing::{debug, info};
 
-use super::types::{KeyCode, KeyCategory};
+use super::phf;
+use super::schema::{self, EntryKind, KeyRecord, ProvenanceRecord};
+use super::types::{KeyCode, KeyCategory, Layout, Provenance};
+use crate::utils::{MacKeyboardError, Result};
 
-/// Static key code database loaded at startup
-pub static KEY_DATABASE: Lazy<KeyDatabase> = Lazy::new(|| {
-    KeyDatabase::load().expect("Failed to load key database")
+/// Environment variable that points the server at an external key data file
+pub const KEY_DATA_ENV_VAR: &str = "MAC_KEYBOARD_MCP_KEYCODES";
+
+/// Tables generated by `build.rs` from `data/keycodes.json`
+mod embedded {
+    use super::{EntryKind, IndexEntry};
+    use crate::keycode::types::{
+        CodeSource, Confidence, HidUsage, KeyCategory, KeyCode, Layout, Provenance, VerificationMethod,
+    };
+    
+    include!(concat!(env!("OUT_DIR"), "/key_tables.rs"));
+}
+
+/// Database built from the embedded key data at compile time
+static EMBEDDED: KeyDatabase = KeyDatabase {
+    keys: &embedded::KEYS,
+    by_code: &embedded::KEYS_BY_CODE,
+    seed: embedded::INDEX_SEED,
+    displacements: &embedded::DISPLACEMENTS,
+    entries: &embedded::ENTRIES,
+};
+
+/// The global database and the source it was loaded from
+static DATABASE: OnceCell<(DataSource, &'static KeyDatabase)> = OnceCell::new();
+
+/// Static key code database
+///
+/// Uses the source passed to [`initialize`] if it was called first, otherwise
+/// the file named by `MAC_KEYBOARD_MCP_KEYCODES` or the embedded data.
+pub static KEY_DATABASE: Lazy<&'static KeyDatabase> = Lazy::new(|| {
+    DATABASE
+        .get_or_try_init(|| load_source(DataSource::from_env()))
+        .expect("Failed to load key database")
+        .1
 });
 
+/// Load the global key database from `source`
+///
+/// Must be called before the first access to [`KEY_DATABASE`]. Calling it
+/// again with the same source returns the loaded database; a different
+/// source is an error rather than being silently ignored.
+pub fn initialize(source: DataSource) -> Result<&'static KeyDatabase> {
+    let (loaded, database) = DATABASE.get_or_try_init(|| load_source(source.clone()))?;
+    if *loaded != source {
+        return Err(MacKeyboardError::DatabaseError(format!(
+            "key database already loaded from {}, cannot load {}", loaded, source
+        )));
+    }
+    Ok(database)
+}
+
+/// Load a database and remember where it came from
+fn load_source(source: DataSource) -> Result<(DataSource, &'static KeyDatabase)> {
+    let database = KeyDatabase::load_from(&source)?;
+    Ok((source, database))
+}
+
+/// Where the key data is read from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DataSource {
+    /// Data compiled into the binary
+    Embedded,
+    /// External JSON file
+    File(PathBuf),
+}
+
+impl DataSource {
+    /// Resolve the source from `MAC_KEYBOARD_MCP_KEYCODES`, falling back to embedded data
+    pub fn from_env() -> Self {
+        match std::env::var_os(KEY_DATA_ENV_VAR) {
+            Some(path) if !path.is_empty() => DataSource::File(PathBuf::from(path)),
+            _ => DataSource::Embedded,
+        }
+    }
+}
+
+impl fmt::Display for DataSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DataSource::Embedded => write!(f, "embedded key data"),
+            DataSource::File(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+/// A lowercased name, alias or shifted symbol in the perfect hash table
+#[derive(Debug)]
+struct IndexEntry {
+    text: &'static str,
+    /// Position of the key in `KeyDatabase::keys`
+    key: u16,
+    kind: EntryKind,
+}
+
 /// Database containing all AppleScript key codes
+///
+/// Lookups hash the query case-insensitively into a perfect hash table over
+/// every name, alias and shifted symbol, so they never allocate.
 pub struct KeyDatabase {
-    /// Lookup by key name (case-insensitive)
-    by_name: HashMap<String, KeyCode>,
-    /// Lookup by key code
-    by_code: HashMap<u16, KeyCode>,
-    /// Keys grouped by category
-    by_category: HashMap<KeyCategory, Vec<KeyCode>>,
-    /// Alias to canonical name mapping
-    aliases: HashMap<String, String>,
+    /// Every key, in data file order
+    keys: &'static [KeyCode],
+    /// Keys ordered by code; keys sharing a code keep data file order
+    by_code: &'static [&'static KeyCode],
+    /// Seed of the perfect hash
+    seed: u64,
+    /// Displacement pair for each hash bucket
+    displacements: &'static [(u32, u32)],
+    /// Hash slots, one per lookup string
+    entries: &'static [IndexEntry],
 }
 
 impl KeyDatabase {
-    /// Load the key database from embedded data
-    pub fn load() -> Result<Self> {
-        info!("Loading key code database...");
-        
-        let mut by_name = HashMap::new();
-        let mut by_code = HashMap::new();
-        let mut by_category = HashMap::new();
-        let mut aliases = HashMap::new();
-        
-        // Load all keys
-        let all_keys = Self::create_all_keys();
-        
-        for key in all_keys {
-            // Add to name lookup (lowercase for case-insensitive)
-            by_name.insert(key.name.to_lowercase(), key.clone());
-            
-            // Add to code lookup
-            by_code.insert(key.code, key.clone());
-            
-            // Add to category
-            by_category
-                .entry(key.category)
-                .or_insert_with(Vec::new)
-                .push(key.clone());
-            
-            // Process aliases
-            for alias in &key.aliases {
-                aliases.insert(alias.to_lowercase(), key.name.clone());
-            }
-        }
+    /// The database compiled from `data/keycodes.json`
+    pub fn embedded() -> &'static KeyDatabase {
+        &EMBEDDED
+    }
+    
+    /// Load the key database from a specific source
+    ///
+    /// Embedded data needs no work at runtime; a data file is parsed and
+    /// validated, and its tables are kept for the life of the process.
+    pub fn load_from(source: &DataSource) -> Result<&'static KeyDatabase> {
+        let path = match source {
+            DataSource::Embedded => return Ok(Self::embedded()),
+            DataSource::File(path) => path,
+        };
         
-        // Sort categories for consistent ordering
-        for keys in by_category.values_mut() {
-            keys.sort_by(|a, b| a.name.cmp(&b.name));
-        }
+        info!("Loading key code database from {}...", source);
         
-        let total_keys = by_name.len();
-        let total_aliases = aliases.len();
-        info!("Loaded {} keys with {} aliases", total_keys, total_aliases);
+        let data = std::fs::read_to_string(path).map_err(|e| {
+            MacKeyboardError::DatabaseError(format!(
+                "cannot read key data file {}: {}", path.display(), e
+            ))
+        })?;
+        
+        let database = Self::from_json(&data).map_err(|e| match e {
+            MacKeyboardError::DatabaseError(msg) => {
+                MacKeyboardError::DatabaseError(format!("{} ({})", msg, source))
+            }
+            other => other,
+        })?;
         
-        Ok(Self {
-            by_name,
-            by_code,
-            by_category,
-            aliases,
-        })
+        Ok(Box::leak(Box::new(database)))
     }
     
-    /// Create all key definitions
-    fn create_all_keys() -> Vec<KeyCode> {
-        let mut keys = Vec::new();
-        
-        // Letters A-Z
-        keys.extend(vec![
-            KeyCode::new("A", 0, KeyCategory::Letters),
-            KeyCode::new("B", 11, KeyCategory::Letters),
-            KeyCode::new("C", 8, KeyCategory::Letters),
-            KeyCode::new("D", 2, KeyCategory::Letters),
-            KeyCode::new("E", 14, KeyCategory::Letters),
-            KeyCode::new("F", 3, KeyCategory::Letters),
-            KeyCode::new("G", 5, KeyCategory::Letters),
-            KeyCode::new("H", 4, KeyCategory::Letters),
-            KeyCode::new("I", 34, KeyCategory::Letters),
-            KeyCode::new("J", 38, KeyCategory::Letters),
-            KeyCode::new("K", 40, KeyCategory::Letters),
-            KeyCode::new("L", 37, KeyCategory::Letters),
-            KeyCode::new("M", 46, KeyCategory::Letters),
-            KeyCode::new("N", 45, KeyCategory::Letters),
-            KeyCode::new("O", 31, KeyCategory::Letters),
-            KeyCode::new("P", 35, KeyCategory::Letters),
-            KeyCode::new("Q", 12, KeyCategory::Letters),
-            KeyCode::new("R", 15, KeyCategory::Letters),
-            KeyCode::new("S", 1, KeyCategory::Letters),
-            KeyCode::new("T", 17, KeyCategory::Letters),
-            KeyCode::new("U", 32, KeyCategory::Letters),
-            KeyCode::new("V", 9, KeyCategory::Letters),
-            KeyCode::new("W", 13, KeyCategory::Letters),
-            KeyCode::new("X", 7, KeyCategory::Letters),
-            KeyCode::new("Y", 16, KeyCategory::Letters),
-            KeyCode::new("Z", 6, KeyCategory::Letters),
-        ]);
-        
-        // Numbers 0-9
-        keys.extend(vec![
-            KeyCode::new("0", 29, KeyCategory::Numbers),
-            KeyCode::new("1", 18, KeyCategory::Numbers),
-            KeyCode::new("2", 19, KeyCategory::Numbers),
-            KeyCode::new("3", 20, KeyCategory::Numbers),
-            KeyCode::new("4", 21, KeyCategory::Numbers),
-            KeyCode::new("5", 23, KeyCategory::Numbers),
-            KeyCode::new("6", 22, KeyCategory::Numbers),
-            KeyCode::new("7", 26, KeyCategory::Numbers),
-            KeyCode::new("8", 28, KeyCategory::Numbers),
-            KeyCode::new("9", 25, KeyCategory::Numbers),
-        ]);
-        
-        // Function keys (corrected based on keyboard image)
-        keys.extend(vec![
-            KeyCode::new("F1", 122, KeyCategory::FunctionKeys),
-            KeyCode::new("F2", 120, KeyCategory::FunctionKeys),
-            KeyCode::new("F3", 99, KeyCategory::FunctionKeys),
-            KeyCode::new("F4", 118, KeyCategory::FunctionKeys),
-            KeyCode::new("F5", 96, KeyCategory::FunctionKeys),
-            KeyCode::new("F6", 97, KeyCategory::FunctionKeys),
-            KeyCode::new("F7", 98, KeyCategory::FunctionKeys),
-            KeyCode::new("F8", 100, KeyCategory::FunctionKeys),
-            KeyCode::new("F9", 101, KeyCategory::FunctionKeys),
-            KeyCode::new("F10", 109, KeyCategory::FunctionKeys),
-            KeyCode::new("F11", 103, KeyCategory::FunctionKeys),
-            KeyCode::new("F12", 111, KeyCategory::FunctionKeys),
-            KeyCode::new("F13", 105, KeyCategory::FunctionKeys)
-                .with_alias("Keyboard Backlight Down"),  // On some Macs
-            KeyCode::new("F14", 107, KeyCategory::FunctionKeys)
-                .with_alias("Brightness Down"),  // Fn+F1 on compact keyboards
-            KeyCode::new("F15", 113, KeyCategory::FunctionKeys)
-                .with_alias("Brightness Up"),    // Fn+F2 on compact keyboards
-            KeyCode::new("F16", 106, KeyCategory::FunctionKeys)
-                .with_alias("Keyboard Backlight Up"),  // On some Macs
-            KeyCode::new("F17", 160, KeyCategory::FunctionKeys)  // This was missing!
-                .with_alias("Mission Control"),  // Fn+F3 on some keyboards
-            KeyCode::new("F18", 131, KeyCategory::FunctionKeys)  // Corrected from 79
-                .with_alias("Launchpad"),        // Fn+F4 on some keyboards
-            KeyCode::new("F19", 80, KeyCategory::FunctionKeys),
-            KeyCode::new("F20", 90, KeyCategory::FunctionKeys),
-        ]);
-        
-        // Modifier keys
-        keys.extend(vec![
-            KeyCode::new("Command", 55, KeyCategory::ModifierKeys)
-                .with_aliases(vec!["Cmd".to_string(), "⌘".to_string()]),
-            KeyCode::new("Shift", 56, KeyCategory::ModifierKeys)
-                .with_alias("⇧"),
-            KeyCode::new("ShiftLeft", 56, KeyCategory::ModifierKeys)
-                .with_alias("Left Shift"),
-            KeyCode::new("ShiftRight", 60, KeyCategory::ModifierKeys)
-                .with_alias("Right Shift"),
-            KeyCode::new("Option", 58, KeyCategory::ModifierKeys)
-                .with_aliases(vec!["Opt".to_string(), "Alt".to_string(), "⌥".to_string()]),
-            KeyCode::new("OptionLeft", 58, KeyCategory::ModifierKeys)
-                .with_alias("Left Option"),
-            KeyCode::new("OptionRight", 61, KeyCategory::ModifierKeys)
-                .with_alias("Right Option"),
-            KeyCode::new("Control", 59, KeyCategory::ModifierKeys)
-                .with_aliases(vec!["Ctrl".to_string(), "⌃".to_string()]),
-            KeyCode::new("ControlLeft", 59, KeyCategory::ModifierKeys)
-                .with_alias("Left Control"),
-            KeyCode::new("ControlRight", 62, KeyCategory::ModifierKeys)
-                .with_alias("Right Control"),
-            KeyCode::new("CapsLock", 57, KeyCategory::ModifierKeys)
-                .with_alias("Caps Lock"),
-            KeyCode::new("Fn", 63, KeyCategory::ModifierKeys)
-                .with_alias("Function"),
-        ]);
-        
-        // Navigation keys
-        keys.extend(vec![
-            KeyCode::new("LeftArrow", 123, KeyCategory::NavigationKeys)
-                .with_aliases(vec!["Left".to_string(), "←".to_string()]),
-            KeyCode::new("RightArrow", 124, KeyCategory::NavigationKeys)
-                .with_aliases(vec!["Right".to_string(), "→".to_string()]),
-            KeyCode::new("UpArrow", 126, KeyCategory::NavigationKeys)
-                .with_aliases(vec!["Up".to_string(), "↑".to_string()]),
-            KeyCode::new("DownArrow", 125, KeyCategory::NavigationKeys)
-                .with_aliases(vec!["Down".to_string(), "↓".to_string()]),
-            KeyCode::new("PageUp", 116, KeyCategory::NavigationKeys)
-                .with_alias("Page Up"),
-            KeyCode::new("PageDown", 121, KeyCategory::NavigationKeys)
-                .with_alias("Page Down"),
-            KeyCode::new("Home", 115, KeyCategory::NavigationKeys),
-            KeyCode::new("End", 119, KeyCategory::NavigationKeys),
-        ]);
-        
-        // Special keys
-        keys.extend(vec![
-            KeyCode::new("Space", 49, KeyCategory::SpecialKeys)
-                .with_aliases(vec!["Spacebar".to_string(), "Space Bar".to_string()]),
-            KeyCode::new("Return", 36, KeyCategory::SpecialKeys)
-                .with_aliases(vec!["Enter".to_string(), "↵".to_string()]),
-            KeyCode::new("Tab", 48, KeyCategory::SpecialKeys)
-                .with_alias("⇥"),
-            KeyCode::new("Delete", 51, KeyCategory::SpecialKeys)
-                .with_aliases(vec!["Backspace".to_string(), "Del".to_string()]),
-            KeyCode::new("ForwardDelete", 117, KeyCategory::SpecialKeys)
-                .with_alias("Forward Delete"),
-            KeyCode::new("Escape", 53, KeyCategory::SpecialKeys)
-                .with_alias("Esc"),
-            KeyCode::new("Clear", 71, KeyCategory::SpecialKeys),
-            KeyCode::new("Help", 114, KeyCategory::SpecialKeys),
-            KeyCode::new("Mute", 74, KeyCategory::SpecialKeys),
-            KeyCode::new("VolumeUp", 72, KeyCategory::SpecialKeys)
-                .with_alias("Volume Up"),
-            KeyCode::new("VolumeDown", 73, KeyCategory::SpecialKeys)
-                .with_alias("Volume Down"),
-        ]);
-        
-        // Numpad keys
-        keys.extend(vec![
-            KeyCode::new("Numpad0", 82, KeyCategory::NumpadKeys),
-            KeyCode::new("Numpad1", 83, KeyCategory::NumpadKeys),
-            KeyCode::new("Numpad2", 84, KeyCategory::NumpadKeys),
-            KeyCode::new("Numpad3", 85, KeyCategory::NumpadKeys),
-            KeyCode::new("Numpad4", 86, KeyCategory::NumpadKeys),
-            KeyCode::new("Numpad5", 87, KeyCategory::NumpadKeys),
-            KeyCode::new("Numpad6", 88, KeyCategory::NumpadKeys),
-            KeyCode::new("Numpad7", 89, KeyCategory::NumpadKeys),
-            KeyCode::new("Numpad8", 91, KeyCategory::NumpadKeys),
-            KeyCode::new("Numpad9", 92, KeyCategory::NumpadKeys),
-            KeyCode::new("NumpadClear", 71, KeyCategory::NumpadKeys),
-            KeyCode::new("NumpadDecimal", 65, KeyCategory::NumpadKeys),
-            KeyCode::new("NumpadDivide", 75, KeyCategory::NumpadKeys),
-            KeyCode::new("NumpadEnter", 76, KeyCategory::NumpadKeys),
-            KeyCode::new("NumpadEquals", 81, KeyCategory::NumpadKeys),
-            KeyCode::new("NumpadMinus", 78, KeyCategory::NumpadKeys),
-            KeyCode::new("NumpadMultiply", 67, KeyCategory::NumpadKeys),
-            KeyCode::new("NumpadPlus", 69, KeyCategory::NumpadKeys),
-        ]);
-        
-        // Punctuation
-        keys.extend(vec![
-            KeyCode::new("Grave", 50, KeyCategory::Punctuation)
-                .with_aliases(vec!["`".to_string(), "~".to_string()]),
-            KeyCode::new("Minus", 27, KeyCategory::Punctuation)
-                .with_aliases(vec!["-".to_string(), "_".to_string()]),
-            KeyCode::new("Equal", 24, KeyCategory::Punctuation)
-                .with_aliases(vec!["=".to_string(), "+".to_string()]),
-            KeyCode::new("LeftBracket", 33, KeyCategory::Punctuation)
-                .with_aliases(vec!["[".to_string(), "{".to_string()]),
-            KeyCode::new("RightBracket", 30, KeyCategory::Punctuation)
-                .with_aliases(vec!["]".to_string(), "}".to_string()]),
-            KeyCode::new("Backslash", 42, KeyCategory::Punctuation)
-                .with_aliases(vec!["\\".to_string(), "|".to_string()]),
-            KeyCode::new("Semicolon", 41, KeyCategory::Punctuation)
-                .with_aliases(vec![";".to_string(), ":".to_string()]),
-            KeyCode::new("Quote", 39, KeyCategory::Punctuation)
-                .with_aliases(vec!["'".to_string(), "\"".to_string()]),
-            KeyCode::new("Comma", 43, KeyCategory::Punctuation)
-                .with_aliases(vec![",".to_string(), "<".to_string()]),
-            KeyCode::new("Period", 47, KeyCategory::Punctuation)
-                .with_aliases(vec![".".to_string(), ">".to_string()]),
-            KeyCode::new("Slash", 44, KeyCategory::Punctuation)
-                .with_aliases(vec!["/".to_string(), "?".to_string()]),
-        ]);
+    /// Build the database from the contents of a key data file
+    ///
+    /// The keys and tables are leaked so they can be handed out as `'static`,
+    /// so every call costs memory for the life of the process. Outside the
+    /// crate, data files are loaded once through [`initialize`].
+    pub(crate) fn from_json(data: &str) -> Result<Self> {
+        let records = schema::parse_key_data(data).map_err(MacKeyboardError::DatabaseError)?;
+        Ok(Self::from_records(records))
+    }
+    
+    /// Build runtime tables from validated records, the same way `build.rs` does
+    fn from_records(records: Vec<KeyRecord>) -> Self {
+        let entries = schema::lookup_entries(&records);
+        let texts: Vec<&str> = entries.iter().map(|(text, _, _)| text.as_str()).collect();
+        let index = phf::build(&texts);
         
-        keys
+        let entries: Vec<IndexEntry> = index.slots
+            .iter()
+            .map(|&slot| {
+                let (text, key, kind) = &entries[slot];
+                IndexEntry { text: leak_str(text.clone()), key: *key as u16, kind: *kind }
+            })
+            .collect();
+        
+        let keys: &'static [KeyCode] = records.into_iter().map(leak_record).collect::<Vec<_>>().leak();
+        let mut by_code: Vec<&'static KeyCode> = keys.iter().collect();
+        by_code.sort_by_key(|key| key.code);
+        
+        info!("Loaded {} keys with {} lookup strings", keys.len(), entries.len());
+        
+        Self {
+            keys,
+            by_code: by_code.leak(),
+            seed: index.seed,
+            displacements: index.displacements.leak(),
+            entries: entries.leak(),
+        }
     }
     
-    /// Look up a key by name (case-insensitive)
+    /// Find the hash table entry for a name, alias or shifted symbol
+    fn entry(&self, text: &str) -> Option<&IndexEntry> {
+        let slot = phf::slot(phf::hash(text, self.seed), self.displacements, self.entries.len())?;
+        let entry = &self.entries[slot];
+        phf::eq_ignore_case(entry.text, text).then_some(entry)
+    }
+    
+    /// Look up a key by name or alias (case-insensitive)
     pub fn lookup(&self, name: &str) -> Option<&KeyCode> {
-        let name_lower = name.to_lowercase();
-        
-        // Try direct lookup
-        if let Some(key) = self.by_name.get(&name_lower) {
-            debug!("Found key '{}' by direct lookup", name);
-            return Some(key);
+        match self.entry(name) {
+            Some(IndexEntry { kind: EntryKind::Name, key, .. }) => {
+                debug!("Found key '{}' by direct lookup", name);
+                Some(&self.keys[*key as usize])
+            }
+            Some(IndexEntry { kind: EntryKind::Alias, key, .. }) => {
+                let key = &self.keys[*key as usize];
+                debug!("Found key '{}' via alias '{}'", key.name, name);
+                Some(key)
+            }
+            _ => {
+                debug!("Key '{}' not found", name);
+                None
+            }
         }
-        
-        // Try alias lookup
-        if let Some(canonical_name) = self.aliases.get(&name_lower) {
-            debug!("Found key '{}' via alias '{}'", canonical_name, name);
-            return self.by_name.get(&canonical_name.to_lowercase());
+    }
+    
+    /// Look up the key that types a shifted symbol (e.g. "{" -> LeftBracket)
+    ///
+    /// Shifted symbols are not aliases: the returned key only produces the
+    /// symbol when pressed together with Shift.
+    pub fn lookup_shifted(&self, symbol: &str) -> Option<&KeyCode> {
+        match self.entry(symbol) {
+            Some(IndexEntry { kind: EntryKind::Shifted, key, .. }) => {
+                let key = &self.keys[*key as usize];
+                debug!("Found key '{}' for shifted symbol '{}'", key.name, symbol);
+                Some(key)
+            }
+            _ => None,
         }
-        
-        debug!("Key '{}' not found", name);
-        None
     }
     
-    /// Look up a key by code
+    /// Look up the canonical key for a code
+    ///
+    /// When several keys share a code (e.g. Shift and ShiftLeft), the one
+    /// defined first in the data file is canonical.
     pub fn lookup_by_code(&self, code: u16) -> Option<&KeyCode> {
-        self.by_code.get(&code)
+        self.lookup_all_by_code(code).first().copied()
+    }
+    
+    /// Look up every key with the given code, canonical key first
+    pub fn lookup_all_by_code(&self, code: u16) -> &[&KeyCode] {
+        let start = self.by_code.partition_point(|key| key.code < code);
+        let end = self.by_code.partition_point(|key| key.code <= code);
+        &self.by_code[start..end]
     }
     
-    /// Get all keys in a category
+    /// Get all keys in a category, sorted by name
     pub fn by_category(&self, category: KeyCategory) -> Vec<&KeyCode> {
-        self.by_category
-            .get(&category)
-            .map(|keys| keys.iter().collect())
-            .unwrap_or_default()
+        let mut keys: Vec<&KeyCode> = self.keys
+            .iter()
+            .filter(|key| key.category == category)
+            .collect();
+        keys.sort_by_key(|key| key.name);
+        keys
     }
     
-    /// Get all keys
-    pub fn all_keys(&self) -> Vec<&KeyCode> {
-        self.by_name.values().collect()
+    /// Get all keys, in data file order
+    pub fn all_keys(&self) -> &[KeyCode] {
+        self.keys
+    }
+    
+    /// Get all keys present on a physical layout
+    pub fn by_layout(&self, layout: Layout) -> Vec<&KeyCode> {
+        self.keys
+            .iter()
+            .filter(|key| key.available_on(layout))
+            .collect()
     }
     
     /// Get all categories with their key counts
     pub fn categories(&self) -> Vec<(KeyCategory, usize)> {
-        let mut categories: Vec<_> = self.by_category
-            .iter()
-            .map(|(cat, keys)| (*cat, keys.len()))
-            .collect();
+        let mut categories: Vec<(KeyCategory, usize)> = Vec::new();
+        for key in self.keys {
+            match categories.iter_mut().find(|(cat, _)| *cat == key.category) {
+                Some((_, count)) => *count += 1,
+                None => categories.push((key.category, 1)),
+            }
+        }
         
         categories.sort_by_key(|(cat, _)| format!("{:?}", cat));
         categories
@@ -324,7 +291,7 @@ impl KeyDatabase {
         let mut results = Vec::new();
         
         // First, try exact matches
-        for key in self.by_name.values() {
+        for key in self.keys {
             if key.matches(query) {
                 results.push(key);
             }
@@ -334,7 +301,7 @@ impl KeyDatabase {
         if results.is_empty() && fuzzy {
             // This will be implemented with the fuzzy search module
             // For now, do simple substring matching
-            for key in self.by_name.values() {
+            for key in self.keys {
                 if key.name.to_lowercase().contains(&query_lower) {
                     results.push(key);
                 }
@@ -345,13 +312,48 @@ impl KeyDatabase {
     }
 }
 
+/// Keep a string for the life of the process
+fn leak_str(text: String) -> &'static str {
+    Box::leak(text.into_boxed_str())
+}
+
+/// Turn a record from a data file into a `'static` key
+fn leak_record(record: KeyRecord) -> KeyCode {
+    KeyCode {
+        name: leak_str(record.name),
+        code: record.code,
+        category: record.category,
+        aliases: record.aliases.into_iter().map(leak_str).collect::<Vec<_>>().leak(),
+        shifted: record.shifted.map(leak_str),
+        layouts: record.layouts.leak(),
+        carbon_constant: record.carbon_constant.map(leak_str),
+        glyph: record.glyph.map(leak_str),
+        hid_usage: record.hid_usage,
+        description: record.description.map(leak_str),
+        printable: record.printable,
+        provenance: record.provenance.map(leak_provenance),
+    }
+}
+
+fn leak_provenance(record: ProvenanceRecord) -> Provenance {
+    Provenance {
+        source: record.source,
+        verified_by: record.verified_by,
+        macos_version: record.macos_version.map(leak_str),
+        keyboard: record.keyboard.map(leak_str),
+        last_verified: record.last_verified.map(leak_str),
+        confidence: record.confidence,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::keycode::types::{CodeSource, Confidence, VerificationMethod};
     
     #[test]
     fn test_database_loading() {
-        let db = KeyDatabase::load().unwrap();
+        let db = KeyDatabase::load_from(&DataSource::from_env()).unwrap();
         
         // Check some basic keys exist
         assert!(db.lookup("A").is_some());
@@ -377,6 +379,20 @@ mod tests {
         assert_eq!(db.lookup("Enter").unwrap().name, "Return");
     }
     
+    #[test]
+    fn test_shifted_lookup() {
+        let db = &KEY_DATABASE;
+        
+        assert_eq!(db.lookup_shifted("{").unwrap().name, "LeftBracket");
+        assert_eq!(db.lookup_shifted("\"").unwrap().name, "Quote");
+        assert_eq!(db.lookup_shifted("!").unwrap().name, "1");
+        assert!(db.lookup_shifted("[").is_none());
+        
+        // Shifted symbols are not aliases of the base key
+        assert!(db.lookup("{").is_none());
+        assert_eq!(db.lookup("[").unwrap().name, "LeftBracket");
+    }
+    
     #[test]
     fn test_category_lookup() {
         let db = &KEY_DATABASE;
@@ -387,4 +403,172 @@ mod tests {
         let numbers = db.by_category(KeyCategory::Numbers);
         assert_eq!(numbers.len(), 10);
     }
-}
\ No newline at end of file
+    
+    #[test]
+    fn test_shared_code_lookup() {
+        let db = &KEY_DATABASE;
+        
+        assert_eq!(db.lookup_by_code(56).unwrap().name, "Shift");
+        assert_eq!(db.lookup_by_code(71).unwrap().name, "Clear");
+        
+        let names: Vec<_> = db.lookup_all_by_code(58).iter().map(|k| k.name).collect();
+        assert_eq!(names, vec!["Option", "OptionLeft"]);
+        
+        assert_eq!(db.lookup_all_by_code(49).len(), 1);
+        assert!(db.lookup_all_by_code(250).is_empty());
+    }
+    
+    #[test]
+    fn test_layout_specific_keys() {
+        let db = &KEY_DATABASE;
+        
+        let section = db.lookup("§").unwrap();
+        assert_eq!(section.code, 10);
+        assert_eq!(section.layouts, vec![Layout::Iso]);
+        
+        assert_eq!(db.lookup("Kana").unwrap().code, 104);
+        assert!(db.by_layout(Layout::Jis).iter().any(|k| k.name == "JisYen"));
+        assert!(!db.by_layout(Layout::Ansi).iter().any(|k| k.name == "JisYen"));
+        assert!(db.by_layout(Layout::Ansi).iter().any(|k| k.name == "A"));
+    }
+    
+    #[test]
+    fn test_embedded_data_is_valid() {
+        let db = KeyDatabase::load_from(&DataSource::Embedded).unwrap();
+        assert_eq!(db.lookup("F18").unwrap().code, 131);
+        
+        // Every key carries a description
+        assert!(db.all_keys().iter().all(|key| key.description.is_some()));
+    }
+    
+    #[test]
+    fn test_generated_tables_match_data_file() {
+        let generated = KeyDatabase::embedded();
+        let parsed = KeyDatabase::from_json(include_str!("../../data/keycodes.json")).unwrap();
+        
+        assert_eq!(generated.all_keys(), parsed.all_keys());
+        for key in parsed.all_keys() {
+            assert_eq!(generated.lookup(key.name), Some(key));
+            for alias in key.aliases {
+                assert_eq!(generated.lookup(alias).map(|k| k.name), Some(key.name));
+            }
+            if let Some(symbol) = key.shifted {
+                assert_eq!(generated.lookup_shifted(symbol).map(|k| k.name), Some(key.name));
+            }
+        }
+    }
+    
+    #[test]
+    fn test_override_data_lookup() {
+        let db = KeyDatabase::from_json(
+            r#"{"version": 1, "keys": [
+                {"name": "Quote", "code": 39, "category": "punctuation", "aliases": ["'"], "shifted": "\""},
+                {"name": "Hyper", "code": 200, "category": "modifier_keys", "aliases": ["✦"]}
+            ]}"#
+        ).unwrap();
+        
+        assert_eq!(db.lookup("HYPER").unwrap().code, 200);
+        assert_eq!(db.lookup("✦").unwrap().name, "Hyper");
+        assert_eq!(db.lookup_shifted("\"").unwrap().name, "Quote");
+        assert!(db.lookup("Space").is_none());
+        assert_eq!(db.all_keys()[0].name, "Quote");
+    }
+    
+    #[test]
+    fn test_key_metadata() {
+        let db = &KEY_DATABASE;
+        
+        let delete = db.lookup("Delete").unwrap();
+        assert_eq!(delete.carbon_constant, Some("kVK_Delete"));
+        assert_eq!(delete.glyph, Some("⌫"));
+        assert!(!delete.printable);
+        
+        let a = db.lookup("A").unwrap();
+        assert_eq!((a.hid_usage.unwrap().page, a.hid_usage.unwrap().id), (0x07, 0x04));
+        assert!(a.printable);
+        
+        assert_eq!(db.lookup("PageUp").unwrap().glyph, Some("⇞"));
+    }
+    
+    #[test]
+    fn test_key_provenance() {
+        let db = &KEY_DATABASE;
+        
+        let space = db.lookup("Space").unwrap().provenance.unwrap();
+        assert_eq!(space.source, CodeSource::AppleHeader);
+        assert_eq!(space.verified_by, Some(VerificationMethod::AutomatedTest));
+        assert_eq!(space.last_verified, Some("2025-07-18"));
+        assert_eq!(space.confidence, Confidence::High);
+        
+        // Codes that disagree with Events.h are flagged
+        assert_eq!(db.lookup("F17").unwrap().confidence(), Confidence::Low);
+        assert_eq!(db.lookup("Kana").unwrap().confidence(), Confidence::Medium);
+        
+        // Every embedded key records where its code comes from
+        assert!(db.all_keys().iter().all(|key| key.provenance.is_some()));
+    }
+    
+    #[test]
+    fn test_data_version_is_checked() {
+        let result = KeyDatabase::from_json(r#"{"version": 99, "keys": []}"#);
+        let err = result.err().unwrap().to_string();
+        assert!(err.contains("unsupported key data version 99"));
+    }
+    
+    #[test]
+    fn test_invalid_data_is_rejected() {
+        // Unknown category
+        assert!(KeyDatabase::from_json(
+            r#"{"version": 1, "keys": [{"name": "A", "code": 0, "category": "vowels"}]}"#
+        ).is_err());
+        
+        // Duplicate names
+        let err = KeyDatabase::from_json(
+            r#"{"version": 1, "keys": [
+                {"name": "A", "code": 0, "category": "letters"},
+                {"name": "a", "code": 1, "category": "letters"}
+            ]}"#
+        ).err().unwrap();
+        assert!(matches!(err, MacKeyboardError::DatabaseError(_)));
+        assert!(err.to_string().contains("duplicate key name"));
+        
+        // Alias shared by two keys
+        assert!(KeyDatabase::from_json(
+            r#"{"version": 1, "keys": [
+                {"name": "A", "code": 0, "category": "letters", "aliases": ["x"]},
+                {"name": "B", "code": 11, "category": "letters", "aliases": ["X"]}
+            ]}"#
+        ).is_err());
+        
+        // Shifted symbol that is also another key's alias
+        let err = KeyDatabase::from_json(
+            r#"{"version": 1, "keys": [
+                {"name": "Equal", "code": 24, "category": "punctuation", "shifted": "+"},
+                {"name": "NumpadPlus", "code": 69, "category": "numpad_keys", "aliases": ["+"]}
+            ]}"#
+        ).err().unwrap();
+        assert!(err.to_string().contains("clashes with an alias"));
+        
+        // Misspelled field
+        assert!(KeyDatabase::from_json(
+            r#"{"version": 1, "keys": [{"name": "A", "code": 0, "category": "letters", "alias": []}]}"#
+        ).is_err());
+    }
+    
+    #[test]
+    fn test_initialize_rejects_a_second_source() {
+        let loaded = initialize(DataSource::from_env()).unwrap();
+        assert!(std::ptr::eq(loaded, *KEY_DATABASE));
+        
+        let other = DataSource::File(PathBuf::from("/other/keycodes.json"));
+        let err = initialize(other).err().unwrap().to_string();
+        assert!(err.contains("already loaded"));
+    }
+    
+    #[test]
+    fn test_missing_file_reports_path() {
+        let source = DataSource::File(PathBuf::from("/nonexistent/keycodes.json"));
+        let err = KeyDatabase::load_from(&source).err().unwrap().to_string();
+        assert!(err.contains("/nonexistent/keycodes.json"));
+    }
+}
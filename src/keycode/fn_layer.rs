@@ -0,0 +1,50 @@
+use crate::keycode::{KeyCode, KEY_DATABASE};
+
+/// Keys that Fn turns into another key, as (pressed with Fn, effective key)
+///
+/// Mac laptop keyboards and the compact Magic Keyboard have no navigation
+/// block, so Home, End, Page Up/Down, Forward Delete and keypad Enter are
+/// only reachable through the Fn (Globe) layer.
+const FN_LAYER: [(&str, &str); 6] = [
+    ("LeftArrow", "Home"),
+    ("RightArrow", "End"),
+    ("UpArrow", "PageUp"),
+    ("DownArrow", "PageDown"),
+    ("Delete", "ForwardDelete"),
+    ("Return", "NumpadEnter"),
+];
+
+/// The key Fn+`key` produces, e.g. Home for LeftArrow
+pub fn fn_layer_key(key: &KeyCode) -> Option<&'static KeyCode> {
+    FN_LAYER
+        .iter()
+        .find(|(pressed, _)| *pressed == key.name)
+        .and_then(|(_, effective)| KEY_DATABASE.lookup(effective))
+}
+
+/// The key to press with Fn to produce `key`, e.g. LeftArrow for Home
+pub fn fn_layer_source(key: &KeyCode) -> Option<&'static KeyCode> {
+    FN_LAYER
+        .iter()
+        .find(|(_, effective)| *effective == key.name)
+        .and_then(|(pressed, _)| KEY_DATABASE.lookup(pressed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_fn_layer_table() {
+        for (pressed, effective) in FN_LAYER {
+            let pressed = KEY_DATABASE.lookup(pressed).unwrap();
+            let effective = KEY_DATABASE.lookup(effective).unwrap();
+            assert_eq!(fn_layer_key(pressed), Some(effective));
+            assert_eq!(fn_layer_source(effective), Some(pressed));
+        }
+        
+        let a = KEY_DATABASE.lookup("A").unwrap();
+        assert!(fn_layer_key(a).is_none());
+        assert!(fn_layer_source(a).is_none());
+    }
+}
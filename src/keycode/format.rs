@@ -0,0 +1,425 @@
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumString};
+
+use crate::keycode::{KeyCategory, KeyCode, KeyCombination, KeySequence, Modifiers};
+
+/// Ways of writing a shortcut for people and other tools
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Display, EnumString)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum ShortcutStyle {
+    /// Full names: "Command+Shift+A"
+    Long,
+    /// Abbreviated names: "Cmd+Shift+A"
+    Short,
+    /// Apple menu glyphs in menu order: "⇧⌘A"
+    Glyphs,
+    /// Emacs key description: "s-A"
+    Emacs,
+    /// VS Code keybinding: "shift+cmd+a"
+    VsCode,
+    /// Electron accelerator: "Command+Shift+A"
+    Electron,
+    /// Nested HTML `<kbd>` markup
+    Html,
+    /// Words for screen readers: "Command Shift A"
+    Spoken,
+}
+
+impl ShortcutStyle {
+    /// All known styles
+    pub const fn all() -> &'static [ShortcutStyle] {
+        &[
+            ShortcutStyle::Long,
+            ShortcutStyle::Short,
+            ShortcutStyle::Glyphs,
+            ShortcutStyle::Emacs,
+            ShortcutStyle::VsCode,
+            ShortcutStyle::Electron,
+            ShortcutStyle::Html,
+            ShortcutStyle::Spoken,
+        ]
+    }
+}
+
+/// Modifier keys as they appear in rendered shortcuts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Modifier {
+    Command,
+    Control,
+    Option,
+    Shift,
+    Fn,
+    CapsLock,
+}
+
+impl Modifier {
+    /// Flags of the modifier on either side, the left key and the right key
+    fn flags(self) -> [Modifiers; 3] {
+        match self {
+            Modifier::Command => [Modifiers::COMMAND, Modifiers::COMMAND_LEFT, Modifiers::COMMAND_RIGHT],
+            Modifier::Control => [Modifiers::CONTROL, Modifiers::CONTROL_LEFT, Modifiers::CONTROL_RIGHT],
+            Modifier::Option => [Modifiers::OPTION, Modifiers::OPTION_LEFT, Modifiers::OPTION_RIGHT],
+            Modifier::Shift => [Modifiers::SHIFT, Modifiers::SHIFT_LEFT, Modifiers::SHIFT_RIGHT],
+            Modifier::Fn => [Modifiers::FN, Modifiers::empty(), Modifiers::empty()],
+            Modifier::CapsLock => [Modifiers::CAPS_LOCK, Modifiers::empty(), Modifiers::empty()],
+        }
+    }
+    
+    /// Order in which a style lists modifiers
+    fn order(style: ShortcutStyle) -> &'static [Modifier] {
+        use Modifier::*;
+        match style {
+            // Apple's menu order ("⌃⌥⇧⌘"), which is also Emacs' "C-M-S-s-"
+            ShortcutStyle::Glyphs | ShortcutStyle::Emacs => &[Fn, CapsLock, Control, Option, Shift, Command],
+            // VS Code's own order on macOS
+            ShortcutStyle::VsCode => &[Fn, CapsLock, Control, Shift, Option, Command],
+            _ => &[Command, Control, Option, Shift, Fn, CapsLock],
+        }
+    }
+    
+    fn name(self, style: ShortcutStyle) -> &'static str {
+        use Modifier::*;
+        match (style, self) {
+            (ShortcutStyle::Short | ShortcutStyle::Html, Command) => "Cmd",
+            (ShortcutStyle::Short | ShortcutStyle::Html, Control) => "Ctrl",
+            (ShortcutStyle::Short | ShortcutStyle::Html, Option) => "Opt",
+            (ShortcutStyle::Glyphs, Command) => "⌘",
+            (ShortcutStyle::Glyphs, Control) => "⌃",
+            (ShortcutStyle::Glyphs, Option) => "⌥",
+            (ShortcutStyle::Glyphs, Shift) => "⇧",
+            (ShortcutStyle::Glyphs, Fn) => "fn",
+            (ShortcutStyle::Glyphs, CapsLock) => "⇪",
+            (ShortcutStyle::Emacs, Command) => "s-",
+            (ShortcutStyle::Emacs, Control) => "C-",
+            (ShortcutStyle::Emacs, Option) => "M-",
+            (ShortcutStyle::Emacs, Shift) => "S-",
+            (ShortcutStyle::Emacs, Fn) => "fn-",
+            (ShortcutStyle::Emacs, CapsLock) => "capslock-",
+            (ShortcutStyle::VsCode, Command) => "cmd",
+            (ShortcutStyle::VsCode, Control) => "ctrl",
+            (ShortcutStyle::VsCode, Option) => "alt",
+            (ShortcutStyle::VsCode, Shift) => "shift",
+            (ShortcutStyle::VsCode, Fn) => "fn",
+            (ShortcutStyle::VsCode, CapsLock) => "capslock",
+            (ShortcutStyle::Electron, Option) => "Alt",
+            (ShortcutStyle::Spoken, CapsLock) => "Caps Lock",
+            (_, Command) => "Command",
+            (_, Control) => "Control",
+            (_, Option) => "Option",
+            (_, Shift) => "Shift",
+            (_, Fn) => "Fn",
+            (_, CapsLock) => "CapsLock",
+        }
+    }
+}
+
+impl Modifier {
+    /// Names of the held keys of this modifier
+    ///
+    /// Styles that can say which side was pressed write one name per key
+    /// ("ShiftRight", "Right Shift"); the others only write the modifier.
+    fn labels(self, held: Modifiers, style: ShortcutStyle) -> Vec<String> {
+        let [plain, left, right] = self.flags();
+        let sided = matches!(
+            style,
+            ShortcutStyle::Long | ShortcutStyle::Short | ShortcutStyle::Html | ShortcutStyle::Spoken
+        );
+        
+        if !sided {
+            return if held.intersects(plain | left | right) {
+                vec![self.name(style).to_string()]
+            } else {
+                Vec::new()
+            };
+        }
+        
+        [(plain, None), (left, Some("Left")), (right, Some("Right"))]
+            .into_iter()
+            .filter(|(flag, _)| !flag.is_empty() && held.contains(*flag))
+            .map(|(_, side)| match (style, side) {
+                (_, None) => self.name(style).to_string(),
+                (ShortcutStyle::Long, Some(side)) => format!("{}{}", self.name(style), side),
+                (_, Some(side)) => format!("{} {}", side, self.name(style)),
+            })
+            .collect()
+    }
+}
+
+impl KeyCombination {
+    /// Render the combination in the given style
+    ///
+    /// `ShortcutStyle::Long` is the `to_string` form. The Emacs, VS Code and
+    /// Electron styles read back with `parse_shortcut_notation`.
+    pub fn render(&self, style: ShortcutStyle) -> String {
+        // Emacs writes Shift+letter as the upper-case letter
+        let shift_letter = style == ShortcutStyle::Emacs
+            && self.modifiers.intersects(Modifiers::ANY_SHIFT)
+            && self.key.category == KeyCategory::Letters;
+        let modifiers = Modifier::order(style)
+            .iter()
+            .filter(|m| !(shift_letter && **m == Modifier::Shift))
+            .flat_map(|m| m.labels(self.modifiers, style));
+        
+        match style {
+            ShortcutStyle::Glyphs => {
+                let mut out: String = modifiers.collect();
+                out.push_str(self.key.glyph.unwrap_or(self.key.name));
+                out
+            }
+            ShortcutStyle::Emacs => {
+                let mut out: String = modifiers.collect();
+                out.push_str(&emacs_key(&self.key, shift_letter));
+                out
+            }
+            ShortcutStyle::Html => {
+                let keys: Vec<String> = modifiers
+                    .chain(std::iter::once(short_key(&self.key).to_string()))
+                    .map(|name| format!("<kbd>{}</kbd>", escape_html(&name)))
+                    .collect();
+                format!("<kbd>{}</kbd>", keys.join("+"))
+            }
+            ShortcutStyle::Spoken => {
+                let mut words: Vec<String> = modifiers.collect();
+                words.push(spoken_key(&self.key));
+                words.join(" ")
+            }
+            _ => {
+                let mut parts: Vec<String> = modifiers.collect();
+                parts.push(match style {
+                    ShortcutStyle::Short => short_key(&self.key).to_string(),
+                    ShortcutStyle::VsCode => vscode_key(&self.key),
+                    ShortcutStyle::Electron => electron_key(&self.key),
+                    _ => self.key.name.to_string(),
+                });
+                parts.join("+")
+            }
+        }
+    }
+}
+
+impl KeySequence {
+    /// Render every chord in the given style, in order
+    pub fn render(&self, style: ShortcutStyle) -> String {
+        let chords: Vec<String> = self.chords.iter().map(|chord| chord.render(style)).collect();
+        match style {
+            ShortcutStyle::Spoken => chords.join(", then "),
+            _ => chords.join(" "),
+        }
+    }
+}
+
+/// The single-character symbol of a punctuation key, such as `-` for Minus
+fn symbol(key: &KeyCode) -> Option<&'static str> {
+    key.aliases
+        .iter()
+        .copied()
+        .find(|alias| alias.len() == 1 && alias.bytes().all(|b| b.is_ascii_punctuation()))
+}
+
+fn short_key(key: &KeyCode) -> &'static str {
+    symbol(key).unwrap_or(key.name)
+}
+
+fn emacs_key(key: &KeyCode, upper_case: bool) -> String {
+    let name = match key.name {
+        "Return" => "RET",
+        "Space" => "SPC",
+        "Tab" => "TAB",
+        "Escape" => "ESC",
+        "Delete" => "DEL",
+        "ForwardDelete" => "<delete>",
+        "PageUp" => "<prior>",
+        "PageDown" => "<next>",
+        "UpArrow" => "<up>",
+        "DownArrow" => "<down>",
+        "LeftArrow" => "<left>",
+        "RightArrow" => "<right>",
+        "NumpadPlus" => "<kp-add>",
+        "NumpadMinus" => "<kp-subtract>",
+        "NumpadMultiply" => "<kp-multiply>",
+        "NumpadDivide" => "<kp-divide>",
+        "NumpadDecimal" => "<kp-decimal>",
+        "NumpadEnter" => "<kp-enter>",
+        "NumpadEquals" => "<kp-equal>",
+        name => {
+            return match (key.category, symbol(key)) {
+                (KeyCategory::Letters, _) if upper_case => name.to_string(),
+                (KeyCategory::Letters | KeyCategory::Numbers, _) => name.to_lowercase(),
+                (_, Some(symbol)) => symbol.to_string(),
+                _ => match name.strip_prefix("Numpad").filter(|n| n.len() == 1) {
+                    Some(digit) => format!("<kp-{}>", digit),
+                    None => format!("<{}>", name.to_lowercase()),
+                },
+            };
+        }
+    };
+    name.to_string()
+}
+
+fn vscode_key(key: &KeyCode) -> String {
+    let name = match key.name {
+        "Return" => "enter",
+        "Delete" => "backspace",
+        "ForwardDelete" => "delete",
+        "UpArrow" => "up",
+        "DownArrow" => "down",
+        "LeftArrow" => "left",
+        "RightArrow" => "right",
+        "NumpadPlus" => "numpad_add",
+        "NumpadMinus" => "numpad_subtract",
+        "NumpadMultiply" => "numpad_multiply",
+        "NumpadDivide" => "numpad_divide",
+        "NumpadDecimal" => "numpad_decimal",
+        name => return symbol(key).map_or(name.to_lowercase(), str::to_string),
+    };
+    name.to_string()
+}
+
+fn electron_key(key: &KeyCode) -> String {
+    let name = match key.name {
+        "Delete" => "Backspace",
+        "ForwardDelete" => "Delete",
+        "UpArrow" => "Up",
+        "DownArrow" => "Down",
+        "LeftArrow" => "Left",
+        "RightArrow" => "Right",
+        "Mute" => "VolumeMute",
+        "NumpadPlus" => "numadd",
+        "NumpadMinus" => "numsub",
+        "NumpadMultiply" => "nummult",
+        "NumpadDivide" => "numdiv",
+        "NumpadDecimal" => "numdec",
+        name => {
+            return match (name.strip_prefix("Numpad").filter(|n| n.len() == 1), symbol(key)) {
+                (Some(digit), _) => format!("num{}", digit),
+                (None, Some(symbol)) => symbol.to_string(),
+                (None, None) => name.to_string(),
+            };
+        }
+    };
+    name.to_string()
+}
+
+/// Key name split into words, e.g. "Left Bracket" or "Numpad 5"
+fn spoken_key(key: &KeyCode) -> String {
+    let mut words = String::new();
+    let mut previous: Option<char> = None;
+    
+    for ch in key.name.chars() {
+        if let Some(prev) = previous {
+            let new_word = (ch.is_uppercase() && prev.is_lowercase())
+                || (ch.is_ascii_digit() && prev.is_alphabetic() && key.name.len() > 3);
+            if new_word {
+                words.push(' ');
+            }
+        }
+        words.push(ch);
+        previous = Some(ch);
+    }
+    
+    words
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keycode::notation::parse_shortcut_notation;
+    use crate::keycode::{parse_shortcut, Notation, KEY_DATABASE};
+    
+    fn render(shortcut: &str, style: ShortcutStyle) -> String {
+        parse_shortcut(shortcut).unwrap().render(style)
+    }
+    
+    #[test]
+    fn test_render_styles() {
+        let shortcut = "Ctrl+Option+Shift+Cmd+A";
+        assert_eq!(render(shortcut, ShortcutStyle::Long), "Command+Control+Option+Shift+A");
+        assert_eq!(render(shortcut, ShortcutStyle::Short), "Cmd+Ctrl+Opt+Shift+A");
+        assert_eq!(render(shortcut, ShortcutStyle::Glyphs), "⌃⌥⇧⌘A");
+        assert_eq!(render(shortcut, ShortcutStyle::Emacs), "C-M-s-A");
+        assert_eq!(render(shortcut, ShortcutStyle::VsCode), "ctrl+shift+alt+cmd+a");
+        assert_eq!(render(shortcut, ShortcutStyle::Electron), "Command+Control+Alt+Shift+A");
+        assert_eq!(render(shortcut, ShortcutStyle::Spoken), "Command Control Option Shift A");
+        
+        assert_eq!(
+            render("Cmd+Shift+A", ShortcutStyle::Html),
+            "<kbd><kbd>Cmd</kbd>+<kbd>Shift</kbd>+<kbd>A</kbd></kbd>"
+        );
+    }
+    
+    #[test]
+    fn test_render_special_keys() {
+        assert_eq!(render("Cmd+-", ShortcutStyle::Short), "Cmd+-");
+        assert_eq!(render("Cmd+Delete", ShortcutStyle::Glyphs), "⌘⌫");
+        assert_eq!(render("Ctrl+Shift+F5", ShortcutStyle::Emacs), "C-S-<f5>");
+        assert_eq!(render("Cmd+ForwardDelete", ShortcutStyle::VsCode), "cmd+delete");
+        assert_eq!(render("Cmd+ForwardDelete", ShortcutStyle::Electron), "Command+Delete");
+        assert_eq!(render("Cmd+Shift+[", ShortcutStyle::Spoken), "Command Shift Left Bracket");
+        assert_eq!(render("Numpad5", ShortcutStyle::Spoken), "Numpad 5");
+    }
+    
+    #[test]
+    fn test_render_sides() {
+        let shortcut = "Right Shift+Left Cmd+A";
+        assert_eq!(render(shortcut, ShortcutStyle::Long), "CommandLeft+ShiftRight+A");
+        assert_eq!(render(shortcut, ShortcutStyle::Short), "Left Cmd+Right Shift+A");
+        assert_eq!(render(shortcut, ShortcutStyle::Spoken), "Left Command Right Shift A");
+        // Styles without sides write the modifier
+        assert_eq!(render(shortcut, ShortcutStyle::Glyphs), "⇧⌘A");
+        assert_eq!(render(shortcut, ShortcutStyle::VsCode), "shift+cmd+a");
+        assert_eq!(render("Fn+CapsLock+F1", ShortcutStyle::Long), "Fn+CapsLock+F1");
+    }
+    
+    #[test]
+    fn test_long_style_matches_to_string() {
+        let combination = parse_shortcut("Cmd+Shift+Page Up").unwrap();
+        assert_eq!(combination.render(ShortcutStyle::Long), combination.to_string());
+    }
+    
+    #[test]
+    fn test_rendered_notations_parse_back() {
+        let styles = [
+            (ShortcutStyle::Emacs, Notation::Emacs),
+            (ShortcutStyle::VsCode, Notation::VsCode),
+            (ShortcutStyle::Electron, Notation::Electron),
+        ];
+        
+        for key in KEY_DATABASE.all_keys() {
+            if key.category == KeyCategory::ModifierKeys {
+                continue;
+            }
+            
+            for modifiers in ["Ctrl+", "Cmd+Shift+", "Opt+"] {
+                let shortcut = format!("{}{}", modifiers, crate::keycode::escape_key_name(key.name));
+                let combination = parse_shortcut(&shortcut).unwrap();
+                
+                for (style, notation) in styles {
+                    let rendered = combination.render(style);
+                    let parsed = parse_shortcut_notation(&rendered, notation)
+                        .unwrap_or_else(|e| panic!("{} ({}) failed: {}", rendered, style, e));
+                    // Modifier order differs between styles
+                    let mut expected = combination.to_keycodes();
+                    let mut actual = parsed.chords[0].to_keycodes();
+                    expected.sort_unstable();
+                    actual.sort_unstable();
+                    assert_eq!(actual, expected, "{}", rendered);
+                }
+            }
+        }
+    }
+    
+    #[test]
+    fn test_render_sequence() {
+        let sequence = parse_shortcut_notation("C-x C-s", Notation::Emacs).unwrap();
+        assert_eq!(sequence.render(ShortcutStyle::Emacs), "C-x C-s");
+        assert_eq!(sequence.render(ShortcutStyle::Spoken), "Control X, then Control S");
+    }
+}
@@ -0,0 +1,257 @@
+use serde::Serialize;
+use std::fmt;
+use std::ops::Range;
+
+use crate::keycode::applescript::modifier_constants;
+use crate::keycode::parser::{dangling_separator, parse_combination, parse_modifiers, word_spans};
+use crate::keycode::{Modifiers, ParseError};
+
+/// How long "hold" keeps the modifiers down when no duration is given
+pub const DEFAULT_HOLD_SECONDS: f64 = 1.0;
+
+/// Pause between the two taps of a double-tap
+const DOUBLE_TAP_INTERVAL: f64 = 0.1;
+
+/// How the modifiers of a gesture are pressed
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum GestureKind {
+    /// Press and release once
+    Tap,
+    /// Press and release twice in quick succession
+    DoubleTap,
+    /// Keep pressed for a while, then release
+    Hold { seconds: f64 },
+}
+
+/// Modifiers pressed without a main key, such as "double-tap Control"
+///
+/// macOS gives these their own meaning: double-tapping Control starts
+/// dictation, pressing Fn alone opens the emoji picker or switches input
+/// source, and holding Option can reveal alternate menu items.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gesture {
+    pub modifiers: Modifiers,
+    pub kind: GestureKind,
+}
+
+impl Gesture {
+    /// Key codes of the modifiers
+    pub fn to_keycodes(&self) -> Vec<u16> {
+        self.modifiers.codes()
+    }
+    
+    /// Generate a System Events script performing the gesture
+    ///
+    /// Command, Control, Option and Shift use `key down`/`key up`. Fn and
+    /// Caps Lock have no `key down` constant, so they are sent with
+    /// `key code`, which taps them even when the gesture holds.
+    pub fn to_applescript(&self) -> String {
+        let mut lines = vec!["tell application \"System Events\"".to_string()];
+        
+        match self.kind {
+            GestureKind::Tap => lines.extend(self.press(None)),
+            GestureKind::DoubleTap => {
+                lines.extend(self.press(None));
+                lines.push(format!("    delay {}", DOUBLE_TAP_INTERVAL));
+                lines.extend(self.press(None));
+            }
+            GestureKind::Hold { seconds } => lines.extend(self.press(Some(seconds))),
+        }
+        
+        lines.push("end tell".to_string());
+        lines.join("\n")
+    }
+    
+    /// Script lines pressing the modifiers once, keeping them down for `hold` seconds
+    fn press(&self, hold: Option<f64>) -> Vec<String> {
+        let constants = modifier_constants(self.modifiers);
+        let tapped = (self.modifiers & (Modifiers::FN | Modifiers::CAPS_LOCK)).codes();
+        
+        let mut lines = Vec::new();
+        if !constants.is_empty() {
+            lines.push(format!("    key down {{{}}}", constants.join(", ")));
+        }
+        for code in tapped {
+            lines.push(format!("    key code {}", code));
+        }
+        if let Some(seconds) = hold {
+            lines.push(format!("    delay {}", seconds));
+        }
+        if !constants.is_empty() {
+            lines.push(format!("    key up {{{}}}", constants.join(", ")));
+        }
+        lines
+    }
+}
+
+impl fmt::Display for Gesture {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let modifiers = self.modifiers.names().join("+");
+        match self.kind {
+            GestureKind::Tap => write!(f, "tap {}", modifiers),
+            GestureKind::DoubleTap => write!(f, "double-tap {}", modifiers),
+            GestureKind::Hold { seconds } => write!(f, "hold {} for {}s", modifiers, seconds),
+        }
+    }
+}
+
+/// Check whether a shortcut is a gesture rather than a key combination
+///
+/// True when it starts with "tap", "double-tap" or "hold", or names only
+/// modifiers ("Fn", "Cmd+Shift").
+pub fn is_gesture(shortcut: &str) -> bool {
+    // "Cmd+" is missing its key rather than tapping Command
+    gesture_kind(shortcut, &word_spans(shortcut)).is_some()
+        || (matches!(parse_combination(shortcut), Err(ParseError::NoMainKey { .. }))
+            && dangling_separator(shortcut).is_none())
+}
+
+/// Parse a modifier-only gesture
+///
+/// Supports:
+/// - "Fn", "Cmd+Shift", "tap Fn" (a single tap)
+/// - "double-tap Control", "double tap Control"
+/// - "hold Option", "hold Option for 2s", "hold Shift 500ms"
+///
+/// A hold without a duration lasts `DEFAULT_HOLD_SECONDS`.
+pub fn parse_gesture(shortcut: &str) -> Result<Gesture, ParseError> {
+    let words = word_spans(shortcut);
+    if words.is_empty() {
+        return Err(ParseError::EmptyShortcut);
+    }
+    
+    let (kind, mut rest) = match gesture_kind(shortcut, &words) {
+        Some((kind, used)) => (kind, &words[used..]),
+        None => (GestureKind::Tap, &words[..]),
+    };
+    
+    let kind = match kind {
+        GestureKind::Hold { .. } => {
+            let mut seconds = DEFAULT_HOLD_SECONDS;
+            let duration = rest.split_last().filter(|(last, _)| starts_with_digit(&shortcut[(*last).clone()]));
+            if let Some((last, init)) = duration {
+                let text = &shortcut[last.clone()];
+                seconds = parse_duration(text).ok_or_else(|| ParseError::InvalidDuration {
+                    text: text.to_string(),
+                    span: last.clone(),
+                })?;
+                rest = init;
+                
+                // "for" joins the modifiers to the duration
+                if let Some((last, init)) = rest.split_last() {
+                    if shortcut[last.clone()].eq_ignore_ascii_case("for") {
+                        rest = init;
+                    }
+                }
+            }
+            GestureKind::Hold { seconds }
+        }
+        kind => kind,
+    };
+    
+    let span: Range<usize> = match (rest.first(), rest.last()) {
+        (Some(first), Some(last)) => first.start..last.end,
+        _ => return Err(ParseError::EmptyShortcut),
+    };
+    let modifiers = parse_modifiers(&shortcut[span.clone()]).map_err(|e| e.offset(span.start))?;
+    
+    Ok(Gesture { modifiers, kind })
+}
+
+/// The gesture named by the leading words and how many words name it
+fn gesture_kind(shortcut: &str, words: &[Range<usize>]) -> Option<(GestureKind, usize)> {
+    let word = |i: usize| words.get(i).map(|span| shortcut[span.clone()].to_lowercase());
+    let hold = GestureKind::Hold { seconds: DEFAULT_HOLD_SECONDS };
+    
+    match word(0)?.as_str() {
+        "tap" => Some((GestureKind::Tap, 1)),
+        "double-tap" | "doubletap" => Some((GestureKind::DoubleTap, 1)),
+        "double" if word(1).as_deref() == Some("tap") => Some((GestureKind::DoubleTap, 2)),
+        "hold" => Some((hold, 1)),
+        _ => None,
+    }
+}
+
+fn starts_with_digit(text: &str) -> bool {
+    text.starts_with(|c: char| c.is_ascii_digit())
+}
+
+/// Seconds in a duration such as "2s", "1.5s" or "500ms"
+fn parse_duration(text: &str) -> Option<f64> {
+    let text = text.to_lowercase();
+    let seconds = if let Some(ms) = text.strip_suffix("ms") {
+        ms.parse::<f64>().ok()? / 1000.0
+    } else {
+        text.strip_suffix('s').unwrap_or(&text).parse::<f64>().ok()?
+    };
+    Some(seconds).filter(|s| s.is_finite() && *s >= 0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_parse_gestures() {
+        let gesture = parse_gesture("double-tap Control").unwrap();
+        assert_eq!(gesture.modifiers, Modifiers::CONTROL);
+        assert_eq!(gesture.kind, GestureKind::DoubleTap);
+        assert_eq!(gesture.to_keycodes(), vec![59]);
+        assert_eq!(parse_gesture("Double Tap Ctrl").unwrap(), gesture);
+        
+        let gesture = parse_gesture("Fn").unwrap();
+        assert_eq!(gesture.kind, GestureKind::Tap);
+        assert_eq!(gesture.to_string(), "tap Fn");
+        
+        let gesture = parse_gesture("hold Right Option for 2s").unwrap();
+        assert_eq!(gesture.modifiers, Modifiers::OPTION_RIGHT);
+        assert_eq!(gesture.kind, GestureKind::Hold { seconds: 2.0 });
+        assert_eq!(gesture.to_string(), "hold OptionRight for 2s");
+        
+        assert_eq!(parse_gesture("hold Cmd+Shift 500ms").unwrap().kind, GestureKind::Hold { seconds: 0.5 });
+        assert_eq!(
+            parse_gesture("hold Option").unwrap().kind,
+            GestureKind::Hold { seconds: DEFAULT_HOLD_SECONDS }
+        );
+    }
+    
+    #[test]
+    fn test_gesture_errors() {
+        assert_eq!(parse_gesture("  "), Err(ParseError::EmptyShortcut));
+        assert_eq!(parse_gesture("hold"), Err(ParseError::EmptyShortcut));
+        assert_eq!(
+            parse_gesture("tap Cmd+A"),
+            Err(ParseError::NotAModifier { key: "A".to_string(), span: 8..9 })
+        );
+        assert_eq!(
+            parse_gesture("hold Option 2x"),
+            Err(ParseError::InvalidDuration { text: "2x".to_string(), span: 12..14 })
+        );
+    }
+    
+    #[test]
+    fn test_is_gesture() {
+        assert!(is_gesture("Cmd+Shift"));
+        assert!(is_gesture("hold Option"));
+        assert!(is_gesture("double tap Fn"));
+        assert!(!is_gesture("Cmd+Shift+A"));
+        assert!(!is_gesture("Cmd+Nope"));
+    }
+    
+    #[test]
+    fn test_gesture_applescript() {
+        let script = parse_gesture("double-tap Control").unwrap().to_applescript();
+        assert_eq!(
+            script,
+            "tell application \"System Events\"\n    key down {control}\n    key up {control}\n    delay 0.1\n    key down {control}\n    key up {control}\nend tell"
+        );
+        
+        let script = parse_gesture("hold Option for 2s").unwrap().to_applescript();
+        assert!(script.contains("key down {option}\n    delay 2\n    key up {option}"));
+        
+        let script = parse_gesture("Fn").unwrap().to_applescript();
+        assert!(script.contains("key code 63"));
+        assert!(!script.contains("key down"));
+    }
+}
@@ -0,0 +1,254 @@
+use crate::keycode::{media_key_for_code, KeyCategory, KeyCode, KeyCombination, KeySequence, MediaKey, Modifiers};
+
+/// Hammerspoon modifier names, as (flags, name)
+///
+/// `hs.hotkey` and `hs.eventtap` do not tell the sides apart, so both
+/// Command keys are "cmd". Caps Lock is not a modifier there.
+const HAMMERSPOON_MODIFIERS: [(Modifiers, &str); 5] = [
+    (Modifiers::ANY_COMMAND, "cmd"),
+    (Modifiers::ANY_SHIFT, "shift"),
+    (Modifiers::ANY_OPTION, "alt"),
+    (Modifiers::ANY_CONTROL, "ctrl"),
+    (Modifiers::FN, "fn"),
+];
+
+/// `hs.keycodes.map` names, as (key name, Hammerspoon name)
+///
+/// Letters, digits and F-keys are their lowercase name and are not listed.
+/// ContextMenu is missing and passed as its raw key code, which
+/// `hs.hotkey.bind` and `hs.eventtap.keyStroke` also accept. Media keys are
+/// system key events rather than key codes (see `media_key`).
+const HAMMERSPOON_KEYS: &[(&str, &str)] = &[
+    ("Command", "cmd"),
+    ("CommandLeft", "cmd"),
+    ("CommandRight", "rightcmd"),
+    ("Shift", "shift"),
+    ("ShiftLeft", "shift"),
+    ("ShiftRight", "rightshift"),
+    ("Option", "alt"),
+    ("OptionLeft", "alt"),
+    ("OptionRight", "rightalt"),
+    ("Control", "ctrl"),
+    ("ControlLeft", "ctrl"),
+    ("ControlRight", "rightctrl"),
+    ("CapsLock", "capslock"),
+    ("Fn", "fn"),
+    ("LeftArrow", "left"),
+    ("RightArrow", "right"),
+    ("UpArrow", "up"),
+    ("DownArrow", "down"),
+    ("PageUp", "pageup"),
+    ("PageDown", "pagedown"),
+    ("Home", "home"),
+    ("End", "end"),
+    ("Space", "space"),
+    ("Return", "return"),
+    ("Tab", "tab"),
+    ("Delete", "delete"),
+    ("ForwardDelete", "forwarddelete"),
+    ("Escape", "escape"),
+    ("Clear", "padclear"),
+    ("Help", "help"),
+    ("Eisu", "eisu"),
+    ("Kana", "kana"),
+    ("Numpad0", "pad0"),
+    ("Numpad1", "pad1"),
+    ("Numpad2", "pad2"),
+    ("Numpad3", "pad3"),
+    ("Numpad4", "pad4"),
+    ("Numpad5", "pad5"),
+    ("Numpad6", "pad6"),
+    ("Numpad7", "pad7"),
+    ("Numpad8", "pad8"),
+    ("Numpad9", "pad9"),
+    ("NumpadClear", "padclear"),
+    ("NumpadDecimal", "pad."),
+    ("NumpadDivide", "pad/"),
+    ("NumpadEnter", "padenter"),
+    ("NumpadEquals", "pad="),
+    ("NumpadMinus", "pad-"),
+    ("NumpadMultiply", "pad*"),
+    ("NumpadPlus", "pad+"),
+    ("JisKeypadComma", "pad,"),
+    ("Grave", "`"),
+    ("Minus", "-"),
+    ("Equal", "="),
+    ("LeftBracket", "["),
+    ("RightBracket", "]"),
+    ("Backslash", "\\"),
+    ("Semicolon", ";"),
+    ("Quote", "'"),
+    ("Comma", ","),
+    ("Period", "."),
+    ("Slash", "/"),
+    ("Section", "§"),
+    ("JisYen", "yen"),
+    ("JisUnderscore", "underscore"),
+];
+
+/// Hammerspoon's name for a key in `hs.keycodes.map`, e.g. "cmd", "a" or "pad+"
+pub fn hammerspoon_key_name(key: &KeyCode) -> Option<String> {
+    match key.category {
+        KeyCategory::Letters | KeyCategory::Numbers | KeyCategory::FunctionKeys => Some(key.name.to_lowercase()),
+        _ => HAMMERSPOON_KEYS
+            .iter()
+            .find(|(name, _)| *name == key.name)
+            .map(|(_, hammerspoon)| hammerspoon.to_string()),
+    }
+}
+
+/// The key as a Lua argument: its quoted name, or its raw key code
+///
+/// None for media keys, which a key code event does not trigger.
+fn key_argument(key: &KeyCode) -> Option<String> {
+    if media_key(key).is_some() {
+        return None;
+    }
+    
+    Some(match hammerspoon_key_name(key) {
+        Some(name) => lua_string(&name),
+        None => key.code.to_string(),
+    })
+}
+
+/// The media key behind a key of the media keys category
+fn media_key(key: &KeyCode) -> Option<&'static MediaKey> {
+    if key.category != KeyCategory::MediaKeys {
+        return None;
+    }
+    media_key_for_code(key.code)
+}
+
+/// `hs.eventtap.event.newSystemKeyEvent` down and up events for a media key, e.g. "SOUND_UP"
+fn system_key_events(media: &MediaKey) -> String {
+    let name = lua_string(media.nx_constant.trim_start_matches("NX_KEYTYPE_"));
+    format!(
+        "hs.eventtap.event.newSystemKeyEvent({0}, true):post()\n\
+         hs.eventtap.event.newSystemKeyEvent({0}, false):post()",
+        name
+    )
+}
+
+/// A Lua string literal
+fn lua_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+impl KeyCombination {
+    /// The modifier table, e.g. `{"cmd", "shift"}`, None when Caps Lock is held
+    fn hammerspoon_modifiers(&self) -> Option<String> {
+        if self.modifiers.contains(Modifiers::CAPS_LOCK) {
+            return None;
+        }
+        
+        let names: Vec<String> = HAMMERSPOON_MODIFIERS
+            .iter()
+            .filter(|(flags, _)| self.modifiers.intersects(*flags))
+            .map(|(_, name)| lua_string(name))
+            .collect();
+        Some(format!("{{{}}}", names.join(", ")))
+    }
+    
+    /// `hs.eventtap.keyStroke({"cmd", "shift"}, "a")`, None when Caps Lock is held
+    ///
+    /// A media key is posted as a system key event instead, and is None when
+    /// modifiers are held with it.
+    pub fn hammerspoon_keystroke(&self) -> Option<String> {
+        if let Some(media) = media_key(&self.key) {
+            return self.modifiers.is_empty().then(|| system_key_events(media));
+        }
+        
+        Some(format!(
+            "hs.eventtap.keyStroke({}, {})",
+            self.hammerspoon_modifiers()?,
+            key_argument(&self.key)?
+        ))
+    }
+    
+    /// An `hs.hotkey.bind` call running the Lua `body` when the combination is pressed
+    ///
+    /// None when Caps Lock is held, which Hammerspoon cannot bind as a modifier,
+    /// or for a media key, which `hs.hotkey` cannot bind.
+    pub fn to_hammerspoon_binding(&self, body: &str) -> Option<String> {
+        let body: Vec<String> = body.lines().map(|line| format!("  {}", line)).collect();
+        Some(format!(
+            "hs.hotkey.bind({}, {}, function()\n{}\nend)",
+            self.hammerspoon_modifiers()?,
+            key_argument(&self.key)?,
+            body.join("\n")
+        ))
+    }
+}
+
+impl KeySequence {
+    /// Lua pressing each chord in turn with `hs.eventtap.keyStroke`
+    ///
+    /// The sequence's `delay` becomes an `hs.timer.usleep` between chords.
+    pub fn to_hammerspoon(&self) -> Option<String> {
+        let mut lines = Vec::new();
+        for (i, chord) in self.chords.iter().enumerate() {
+            if let (Some(seconds), true) = (self.delay, i > 0) {
+                lines.push(format!("hs.timer.usleep({})", (seconds * 1_000_000.0).round() as u64));
+            }
+            lines.push(chord.hammerspoon_keystroke()?);
+        }
+        Some(lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keycode::{parse_shortcut, parse_shortcut_sequence, KEY_DATABASE};
+    
+    #[test]
+    fn test_hammerspoon_names() {
+        let name = |key: &str| hammerspoon_key_name(KEY_DATABASE.lookup(key).unwrap());
+        assert_eq!(name("A").as_deref(), Some("a"));
+        assert_eq!(name("F13").as_deref(), Some("f13"));
+        assert_eq!(name("OptionRight").as_deref(), Some("rightalt"));
+        assert_eq!(name("NumpadPlus").as_deref(), Some("pad+"));
+        assert_eq!(name("JisUnderscore").as_deref(), Some("underscore"));
+        assert!(name("VolumeUp").is_none());
+        
+        for (key, _) in HAMMERSPOON_KEYS {
+            assert!(KEY_DATABASE.lookup(key).is_some(), "{}", key);
+        }
+    }
+    
+    #[test]
+    fn test_hammerspoon_keystroke() {
+        let stroke = |shortcut: &str| parse_shortcut(shortcut).unwrap().hammerspoon_keystroke();
+        assert_eq!(stroke("Cmd+Shift+A").as_deref(), Some("hs.eventtap.keyStroke({\"cmd\", \"shift\"}, \"a\")"));
+        assert_eq!(stroke("Escape").as_deref(), Some("hs.eventtap.keyStroke({}, \"escape\")"));
+        assert_eq!(stroke("OptionRight+\\").as_deref(), Some("hs.eventtap.keyStroke({\"alt\"}, \"\\\\\")"));
+        assert_eq!(stroke("ContextMenu").as_deref(), Some("hs.eventtap.keyStroke({}, 110)"));
+        assert!(stroke("CapsLock+A").is_none());
+        
+        // Media keys are system key events, which take no modifiers
+        assert_eq!(
+            stroke("VolumeUp").as_deref(),
+            Some("hs.eventtap.event.newSystemKeyEvent(\"SOUND_UP\", true):post()\n\
+                  hs.eventtap.event.newSystemKeyEvent(\"SOUND_UP\", false):post()")
+        );
+        assert!(stroke("Cmd+VolumeUp").is_none());
+        
+        let sequence = KeySequence { delay: Some(0.2), ..parse_shortcut_sequence("Ctrl+X Ctrl+S").unwrap() };
+        assert_eq!(
+            sequence.to_hammerspoon().unwrap(),
+            "hs.eventtap.keyStroke({\"ctrl\"}, \"x\")\n\
+             hs.timer.usleep(200000)\n\
+             hs.eventtap.keyStroke({\"ctrl\"}, \"s\")"
+        );
+    }
+    
+    #[test]
+    fn test_hammerspoon_binding() {
+        let binding = parse_shortcut("Cmd+Alt+Left").unwrap().to_hammerspoon_binding("hs.alert.show(\"left\")");
+        assert_eq!(
+            binding.as_deref(),
+            Some("hs.hotkey.bind({\"cmd\", \"alt\"}, \"left\", function()\n  hs.alert.show(\"left\")\nend)")
+        );
+        assert!(parse_shortcut("Mute").unwrap().to_hammerspoon_binding("").is_none());
+    }
+}
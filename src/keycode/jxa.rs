@@ -0,0 +1,73 @@
+use crate::keycode::applescript::modifier_constants;
+use crate::keycode::{KeyCombination, KeySequence};
+
+impl KeyCombination {
+    /// The `keyCode(...)` call that presses the combination on a System Events object
+    fn jxa_call(&self, target: &str) -> String {
+        let using: Vec<String> = modifier_constants(self.modifiers)
+            .into_iter()
+            .map(|constant| format!("'{} down'", constant))
+            .collect();
+        
+        match using.as_slice() {
+            [] => format!("{}.keyCode({});", target, self.key.code),
+            [one] => format!("{}.keyCode({}, {{using: {}}});", target, self.key.code, one),
+            many => format!("{}.keyCode({}, {{using: [{}]}});", target, self.key.code, many.join(", ")),
+        }
+    }
+    
+    /// One-line JavaScript for Automation statement pressing the combination
+    ///
+    /// `Application('System Events').keyCode(0, {using: 'command down'});`
+    pub fn to_jxa(&self) -> String {
+        self.jxa_call("Application('System Events')")
+    }
+}
+
+impl KeySequence {
+    /// JavaScript for Automation script pressing each chord in turn
+    ///
+    /// The sequence's `delay` is inserted between chords.
+    pub fn to_jxa(&self) -> String {
+        if self.is_single() {
+            return self.chords[0].to_jxa();
+        }
+        
+        let mut lines = vec!["const systemEvents = Application('System Events');".to_string()];
+        for (i, chord) in self.chords.iter().enumerate() {
+            if let (Some(seconds), true) = (self.delay, i > 0) {
+                lines.push(format!("delay({});", seconds));
+            }
+            lines.push(chord.jxa_call("systemEvents"));
+        }
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::keycode::{parse_shortcut, parse_shortcut_sequence, KeySequence};
+    
+    #[test]
+    fn test_combination_jxa() {
+        let jxa = |shortcut: &str| parse_shortcut(shortcut).unwrap().to_jxa();
+        assert_eq!(jxa("Cmd+A"), "Application('System Events').keyCode(0, {using: 'command down'});");
+        assert_eq!(
+            jxa("Cmd+Shift+Z"),
+            "Application('System Events').keyCode(6, {using: ['command down', 'shift down']});"
+        );
+        assert_eq!(jxa("Escape"), "Application('System Events').keyCode(53);");
+    }
+    
+    #[test]
+    fn test_sequence_jxa() {
+        let sequence = KeySequence { delay: Some(0.2), ..parse_shortcut_sequence("Ctrl+X Ctrl+S").unwrap() };
+        assert_eq!(
+            sequence.to_jxa(),
+            "const systemEvents = Application('System Events');\n\
+             systemEvents.keyCode(7, {using: 'control down'});\n\
+             delay(0.2);\n\
+             systemEvents.keyCode(1, {using: 'control down'});"
+        );
+    }
+}
@@ -0,0 +1,283 @@
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::keycode::{KeyCategory, KeyCode, KeyCombination, KeySequence, Modifiers};
+
+/// Karabiner-Elements `key_code` names, as (key name, Karabiner name)
+///
+/// Letters, digits and F-keys are their lowercase name and are not listed.
+/// Karabiner names keys after their HID usage, so ISO Section is
+/// `non_us_backslash`, Help is `insert` and the keypad Clear is `keypad_num_lock`.
+const KARABINER_KEY_CODES: &[(&str, &str)] = &[
+    ("Command", "left_command"),
+    ("CommandLeft", "left_command"),
+    ("CommandRight", "right_command"),
+    ("Shift", "left_shift"),
+    ("ShiftLeft", "left_shift"),
+    ("ShiftRight", "right_shift"),
+    ("Option", "left_option"),
+    ("OptionLeft", "left_option"),
+    ("OptionRight", "right_option"),
+    ("Control", "left_control"),
+    ("ControlLeft", "left_control"),
+    ("ControlRight", "right_control"),
+    ("CapsLock", "caps_lock"),
+    ("Fn", "fn"),
+    ("LeftArrow", "left_arrow"),
+    ("RightArrow", "right_arrow"),
+    ("UpArrow", "up_arrow"),
+    ("DownArrow", "down_arrow"),
+    ("PageUp", "page_up"),
+    ("PageDown", "page_down"),
+    ("Home", "home"),
+    ("End", "end"),
+    ("Space", "spacebar"),
+    ("Return", "return_or_enter"),
+    ("Tab", "tab"),
+    ("Delete", "delete_or_backspace"),
+    ("ForwardDelete", "delete_forward"),
+    ("Escape", "escape"),
+    ("Clear", "keypad_num_lock"),
+    ("Help", "insert"),
+    ("ContextMenu", "application"),
+    ("Eisu", "japanese_eisuu"),
+    ("Kana", "japanese_kana"),
+    ("Mute", "mute"),
+    ("VolumeUp", "volume_increment"),
+    ("VolumeDown", "volume_decrement"),
+    ("Numpad0", "keypad_0"),
+    ("Numpad1", "keypad_1"),
+    ("Numpad2", "keypad_2"),
+    ("Numpad3", "keypad_3"),
+    ("Numpad4", "keypad_4"),
+    ("Numpad5", "keypad_5"),
+    ("Numpad6", "keypad_6"),
+    ("Numpad7", "keypad_7"),
+    ("Numpad8", "keypad_8"),
+    ("Numpad9", "keypad_9"),
+    ("NumpadClear", "keypad_num_lock"),
+    ("NumpadDecimal", "keypad_period"),
+    ("NumpadDivide", "keypad_slash"),
+    ("NumpadEnter", "keypad_enter"),
+    ("NumpadEquals", "keypad_equal_sign"),
+    ("NumpadMinus", "keypad_hyphen"),
+    ("NumpadMultiply", "keypad_asterisk"),
+    ("NumpadPlus", "keypad_plus"),
+    ("JisKeypadComma", "keypad_comma"),
+    ("Grave", "grave_accent_and_tilde"),
+    ("Minus", "hyphen"),
+    ("Equal", "equal_sign"),
+    ("LeftBracket", "open_bracket"),
+    ("RightBracket", "close_bracket"),
+    ("Backslash", "backslash"),
+    ("Semicolon", "semicolon"),
+    ("Quote", "quote"),
+    ("Comma", "comma"),
+    ("Period", "period"),
+    ("Slash", "slash"),
+    ("Section", "non_us_backslash"),
+    ("JisYen", "international3"),
+    ("JisUnderscore", "international1"),
+];
+
+/// Karabiner modifier names, as (flag, name)
+///
+/// The plain modifier is Karabiner's either-side name, so a `from` with
+/// `command` matches both Command keys.
+const KARABINER_MODIFIERS: [(Modifiers, &str); 14] = [
+    (Modifiers::COMMAND, "command"),
+    (Modifiers::COMMAND_LEFT, "left_command"),
+    (Modifiers::COMMAND_RIGHT, "right_command"),
+    (Modifiers::CONTROL, "control"),
+    (Modifiers::CONTROL_LEFT, "left_control"),
+    (Modifiers::CONTROL_RIGHT, "right_control"),
+    (Modifiers::OPTION, "option"),
+    (Modifiers::OPTION_LEFT, "left_option"),
+    (Modifiers::OPTION_RIGHT, "right_option"),
+    (Modifiers::SHIFT, "shift"),
+    (Modifiers::SHIFT_LEFT, "left_shift"),
+    (Modifiers::SHIFT_RIGHT, "right_shift"),
+    (Modifiers::FN, "fn"),
+    (Modifiers::CAPS_LOCK, "caps_lock"),
+];
+
+/// Karabiner-Elements' `key_code` for a key, e.g. "left_command" or "grave_accent_and_tilde"
+pub fn karabiner_key_code(key: &KeyCode) -> Option<String> {
+    match key.category {
+        KeyCategory::Letters | KeyCategory::Numbers | KeyCategory::FunctionKeys => Some(key.name.to_lowercase()),
+        _ => KARABINER_KEY_CODES
+            .iter()
+            .find(|(name, _)| *name == key.name)
+            .map(|(_, karabiner)| karabiner.to_string()),
+    }
+}
+
+/// Errors turning a shortcut mapping into a Karabiner manipulator
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum KarabinerError {
+    #[error("Karabiner-Elements has no key_code for {key}")]
+    UnknownKey { key: String },
+    
+    #[error("The from shortcut must be a single chord, not a sequence of {chords}")]
+    FromSequence { chords: usize },
+}
+
+/// A `complex_modifications` rule: one description and its manipulators
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct KarabinerRule {
+    pub description: String,
+    pub manipulators: Vec<KarabinerManipulator>,
+}
+
+/// A `basic` manipulator turning one chord into a series of chords
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct KarabinerManipulator {
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub from: KarabinerFrom,
+    pub to: Vec<KarabinerTo>,
+}
+
+/// The chord a manipulator matches
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct KarabinerFrom {
+    pub key_code: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modifiers: Option<KarabinerFromModifiers>,
+}
+
+/// Modifiers that must be held for `from` to match
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct KarabinerFromModifiers {
+    pub mandatory: Vec<&'static str>,
+}
+
+/// One chord a manipulator sends
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct KarabinerTo {
+    pub key_code: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub modifiers: Vec<&'static str>,
+}
+
+impl Modifiers {
+    /// Karabiner modifier names, e.g. ["command", "right_shift"]
+    pub fn karabiner_names(self) -> Vec<&'static str> {
+        KARABINER_MODIFIERS
+            .iter()
+            .filter(|(flag, _)| self.contains(*flag))
+            .map(|(_, name)| *name)
+            .collect()
+    }
+}
+
+impl KeyCombination {
+    /// The key and modifiers pressed on the keyboard, undoing the Fn-layer translation
+    ///
+    /// Karabiner sees the physical keys, so "Fn+Left" is `left_arrow` with
+    /// `fn` held rather than `home`.
+    fn physical(&self) -> (&KeyCode, Modifiers) {
+        match &self.fn_key {
+            Some(pressed) => (pressed, self.modifiers | Modifiers::FN),
+            None => (&self.key, self.modifiers),
+        }
+    }
+    
+    /// The Karabiner `from` event matching the combination
+    pub fn to_karabiner_from(&self) -> Result<KarabinerFrom, KarabinerError> {
+        let (key, modifiers) = self.physical();
+        let mandatory = modifiers.karabiner_names();
+        Ok(KarabinerFrom {
+            key_code: key_code(key)?,
+            modifiers: (!mandatory.is_empty()).then_some(KarabinerFromModifiers { mandatory }),
+        })
+    }
+    
+    /// The Karabiner `to` event sending the combination
+    pub fn to_karabiner_to(&self) -> Result<KarabinerTo, KarabinerError> {
+        Ok(KarabinerTo {
+            key_code: key_code(&self.key)?,
+            modifiers: self.modifiers.karabiner_names(),
+        })
+    }
+}
+
+/// Karabiner `key_code` or the error naming the key
+fn key_code(key: &KeyCode) -> Result<String, KarabinerError> {
+    karabiner_key_code(key).ok_or_else(|| KarabinerError::UnknownKey { key: key.name.to_string() })
+}
+
+impl KarabinerManipulator {
+    /// Manipulator sending every chord of `to` when the single chord `from` is pressed
+    pub fn new(from: &KeySequence, to: &KeySequence) -> Result<Self, KarabinerError> {
+        if !from.is_single() {
+            return Err(KarabinerError::FromSequence { chords: from.chords.len() });
+        }
+        
+        Ok(KarabinerManipulator {
+            kind: "basic",
+            from: from.chords[0].to_karabiner_from()?,
+            to: to.chords.iter().map(KeyCombination::to_karabiner_to).collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keycode::{parse_shortcut, parse_shortcut_sequence, KEY_DATABASE};
+    
+    #[test]
+    fn test_every_key_has_a_karabiner_name() {
+        for key in KEY_DATABASE.all_keys() {
+            assert!(karabiner_key_code(key).is_some(), "{}", key.name);
+        }
+        
+        let name = |key: &str| karabiner_key_code(KEY_DATABASE.lookup(key).unwrap());
+        assert_eq!(name("CommandLeft").as_deref(), Some("left_command"));
+        assert_eq!(name("Grave").as_deref(), Some("grave_accent_and_tilde"));
+        assert_eq!(name("A").as_deref(), Some("a"));
+        assert_eq!(name("F5").as_deref(), Some("f5"));
+    }
+    
+    #[test]
+    fn test_manipulator_json() {
+        let from = parse_shortcut_sequence("Cmd+Shift+A").unwrap();
+        let to = parse_shortcut_sequence("Ctrl+B OptionRight+Escape").unwrap();
+        let manipulator = KarabinerManipulator::new(&from, &to).unwrap();
+        assert_eq!(
+            serde_json::to_value(&manipulator).unwrap(),
+            serde_json::json!({
+                "type": "basic",
+                "from": {
+                    "key_code": "a",
+                    "modifiers": { "mandatory": ["command", "shift"] }
+                },
+                "to": [
+                    { "key_code": "b", "modifiers": ["control"] },
+                    { "key_code": "escape", "modifiers": ["right_option"] }
+                ]
+            })
+        );
+        
+        let escape = parse_shortcut("Escape").unwrap().to_karabiner_from().unwrap();
+        assert_eq!(serde_json::to_value(escape).unwrap(), serde_json::json!({ "key_code": "escape" }));
+    }
+    
+    #[test]
+    fn test_from_is_physical() {
+        // Fn+Left is matched as the arrow with Fn held, but sent as Home
+        let combination = parse_shortcut("Fn+Left").unwrap();
+        let from = combination.to_karabiner_from().unwrap();
+        assert_eq!(from.key_code, "left_arrow");
+        assert_eq!(from.modifiers.unwrap().mandatory, vec!["fn"]);
+        assert_eq!(combination.to_karabiner_to().unwrap().key_code, "home");
+    }
+    
+    #[test]
+    fn test_from_sequence_rejected() {
+        let from = parse_shortcut_sequence("Ctrl+X Ctrl+S").unwrap();
+        let to = parse_shortcut_sequence("Cmd+S").unwrap();
+        assert_eq!(KarabinerManipulator::new(&from, &to), Err(KarabinerError::FromSequence { chords: 2 }));
+    }
+}
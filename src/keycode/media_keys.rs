@@ -0,0 +1,324 @@
+/// Media and system keys (volume, brightness, playback, ...)
+///
+/// These keys are not delivered as ordinary virtual key codes. macOS posts them
+/// as `NSSystemDefined` events (subtype 8) carrying an `NX_KEYTYPE_*` value from
+/// `<IOKit/hidsystem/ev_keymap.h>`, so `tell application "System Events" to key code`
+/// cannot trigger them. The `alternatives` of each key show what to use instead.
+pub static MEDIA_KEYS: &[MediaKey] = &[
+    MediaKey {
+        name: "VolumeUp",
+        aliases: &["Volume Up", "Sound Up"],
+        nx_keytype: 0,
+        nx_constant: "NX_KEYTYPE_SOUND_UP",
+        key_code: Some(72),
+        triggerable_by_key_code: false,
+        description: "Increase output volume",
+    },
+    MediaKey {
+        name: "VolumeDown",
+        aliases: &["Volume Down", "Sound Down"],
+        nx_keytype: 1,
+        nx_constant: "NX_KEYTYPE_SOUND_DOWN",
+        key_code: Some(73),
+        triggerable_by_key_code: false,
+        description: "Decrease output volume",
+    },
+    MediaKey {
+        name: "BrightnessUp",
+        aliases: &["Brightness Up"],
+        nx_keytype: 2,
+        nx_constant: "NX_KEYTYPE_BRIGHTNESS_UP",
+        key_code: None,
+        triggerable_by_key_code: false,
+        description: "Increase display brightness",
+    },
+    MediaKey {
+        name: "BrightnessDown",
+        aliases: &["Brightness Down"],
+        nx_keytype: 3,
+        nx_constant: "NX_KEYTYPE_BRIGHTNESS_DOWN",
+        key_code: None,
+        triggerable_by_key_code: false,
+        description: "Decrease display brightness",
+    },
+    MediaKey {
+        name: "Mute",
+        aliases: &["Volume Mute"],
+        nx_keytype: 7,
+        nx_constant: "NX_KEYTYPE_MUTE",
+        key_code: Some(74),
+        triggerable_by_key_code: false,
+        description: "Mute or unmute output audio",
+    },
+    MediaKey {
+        name: "Launchpad",
+        aliases: &["Launch Panel"],
+        nx_keytype: 13,
+        nx_constant: "NX_KEYTYPE_LAUNCH_PANEL",
+        key_code: None,
+        triggerable_by_key_code: false,
+        description: "Show all apps",
+    },
+    MediaKey {
+        name: "Eject",
+        aliases: &["⏏"],
+        nx_keytype: 14,
+        nx_constant: "NX_KEYTYPE_EJECT",
+        key_code: None,
+        triggerable_by_key_code: false,
+        description: "Eject removable media",
+    },
+    MediaKey {
+        name: "PlayPause",
+        aliases: &["Play/Pause", "Play", "Pause"],
+        nx_keytype: 16,
+        nx_constant: "NX_KEYTYPE_PLAY",
+        key_code: None,
+        triggerable_by_key_code: false,
+        description: "Play or pause media",
+    },
+    MediaKey {
+        name: "NextTrack",
+        aliases: &["Next Track", "Next"],
+        nx_keytype: 17,
+        nx_constant: "NX_KEYTYPE_NEXT",
+        key_code: None,
+        triggerable_by_key_code: false,
+        description: "Skip to next track",
+    },
+    MediaKey {
+        name: "PreviousTrack",
+        aliases: &["Previous Track", "Previous"],
+        nx_keytype: 18,
+        nx_constant: "NX_KEYTYPE_PREVIOUS",
+        key_code: None,
+        triggerable_by_key_code: false,
+        description: "Skip to previous track",
+    },
+    MediaKey {
+        name: "FastForward",
+        aliases: &["Fast Forward"],
+        nx_keytype: 19,
+        nx_constant: "NX_KEYTYPE_FAST",
+        key_code: None,
+        triggerable_by_key_code: false,
+        description: "Fast-forward the current track",
+    },
+    MediaKey {
+        name: "Rewind",
+        aliases: &[],
+        nx_keytype: 20,
+        nx_constant: "NX_KEYTYPE_REWIND",
+        key_code: None,
+        triggerable_by_key_code: false,
+        description: "Rewind the current track",
+    },
+    MediaKey {
+        name: "IlluminationUp",
+        aliases: &["Illumination Up", "Keyboard Illumination Up"],
+        nx_keytype: 21,
+        nx_constant: "NX_KEYTYPE_ILLUMINATION_UP",
+        key_code: None,
+        triggerable_by_key_code: false,
+        description: "Increase keyboard backlight",
+    },
+    MediaKey {
+        name: "IlluminationDown",
+        aliases: &["Illumination Down", "Keyboard Illumination Down"],
+        nx_keytype: 22,
+        nx_constant: "NX_KEYTYPE_ILLUMINATION_DOWN",
+        key_code: None,
+        triggerable_by_key_code: false,
+        description: "Decrease keyboard backlight",
+    },
+    MediaKey {
+        name: "IlluminationToggle",
+        aliases: &["Illumination Toggle"],
+        nx_keytype: 23,
+        nx_constant: "NX_KEYTYPE_ILLUMINATION_TOGGLE",
+        key_code: None,
+        triggerable_by_key_code: false,
+        description: "Toggle keyboard backlight",
+    },
+];
+
+/// JXA snippet posting a media key down/up pair; KEYTYPE is replaced with the NX value
+const SYSTEM_DEFINED_JXA: &str = r#"ObjC.import('AppKit');
+function post(down) {
+    const ev = $.NSEvent.otherEventWithTypeLocationModifierFlagsTimestampWindowNumberContextSubtypeData1Data2(
+        $.NSEventTypeSystemDefined, $.NSMakePoint(0, 0), down ? 0xa00 : 0xb00, 0, 0, $(), 8,
+        (KEYTYPE << 16) | ((down ? 0xa : 0xb) << 8), -1);
+    $.CGEventPost($.kCGHIDEventTap, ev.CGEvent);
+}
+post(true);
+post(false);"#;
+
+/// Swift snippet posting a media key down/up pair; KEYTYPE and CONSTANT are replaced
+const SYSTEM_DEFINED_SWIFT: &str = r#"import AppKit
+
+func postMediaKey(_ keyType: Int) {
+    for down in [true, false] {
+        let event = NSEvent.otherEvent(
+            with: .systemDefined, location: .zero,
+            modifierFlags: NSEvent.ModifierFlags(rawValue: down ? 0xa00 : 0xb00),
+            timestamp: 0, windowNumber: 0, context: nil, subtype: 8,
+            data1: (keyType << 16) | ((down ? 0xa : 0xb) << 8), data2: -1)
+        event?.cgEvent?.post(tap: .cghidEventTap)
+    }
+}
+
+postMediaKey(KEYTYPE) // CONSTANT"#;
+
+/// A media or system key identified by its `NX_KEYTYPE_*` value
+#[derive(Debug, Clone)]
+pub struct MediaKey {
+    pub name: &'static str,
+    pub aliases: &'static [&'static str],
+    /// `NX_KEYTYPE_*` value sent in the system-defined event
+    pub nx_keytype: u8,
+    /// Name of the `NX_KEYTYPE_*` constant
+    pub nx_constant: &'static str,
+    /// Virtual key code Carbon assigns to the key, if any
+    pub key_code: Option<u16>,
+    /// Whether `tell application "System Events" to key code` triggers the key
+    ///
+    /// False even for the keys with a Carbon key code (VolumeUp, VolumeDown,
+    /// Mute): they only act as media keys when sent as system-defined events.
+    pub triggerable_by_key_code: bool,
+    pub description: &'static str,
+}
+
+/// A way to trigger a media key other than `key code`
+#[derive(Debug, Clone)]
+pub struct Alternative {
+    /// Language of the snippet ("applescript", "jxa", "swift")
+    pub language: &'static str,
+    pub description: &'static str,
+    pub code: String,
+}
+
+impl MediaKey {
+    /// Check if this key matches a name, ignoring case, spaces, '-' and '_'
+    pub fn matches(&self, query: &str) -> bool {
+        let query = normalize(query);
+        
+        normalize(self.name) == query
+            || self.aliases.iter().any(|alias| normalize(alias) == query)
+    }
+    
+    /// Generate scripts that trigger this key without `key code`
+    pub fn alternatives(&self) -> Vec<Alternative> {
+        let mut alternatives = Vec::new();
+        
+        // Native AppleScript commands where one exists
+        let applescript = match self.name {
+            "VolumeUp" => Some((
+                "Raise output volume by one step",
+                "set volume output volume ((output volume of (get volume settings)) + 6)",
+            )),
+            "VolumeDown" => Some((
+                "Lower output volume by one step",
+                "set volume output volume ((output volume of (get volume settings)) - 6)",
+            )),
+            "Mute" => Some((
+                "Toggle output mute",
+                "set volume output muted (not (output muted of (get volume settings)))",
+            )),
+            "PlayPause" => Some(("Toggle playback in Music", "tell application \"Music\" to playpause")),
+            "NextTrack" => Some(("Skip forward in Music", "tell application \"Music\" to next track")),
+            "PreviousTrack" => Some(("Skip back in Music", "tell application \"Music\" to previous track")),
+            "FastForward" => Some(("Fast-forward in Music", "tell application \"Music\" to fast forward")),
+            "Rewind" => Some(("Rewind in Music", "tell application \"Music\" to rewind")),
+            _ => None,
+        };
+        
+        if let Some((description, code)) = applescript {
+            alternatives.push(Alternative {
+                language: "applescript",
+                description,
+                code: code.to_string(),
+            });
+        }
+        
+        // System-defined event, runnable with `osascript -l JavaScript`
+        alternatives.push(Alternative {
+            language: "jxa",
+            description: "Post the system-defined media key event (osascript -l JavaScript)",
+            code: SYSTEM_DEFINED_JXA.replace("KEYTYPE", &self.nx_keytype.to_string()),
+        });
+        
+        alternatives.push(Alternative {
+            language: "swift",
+            description: "Post the system-defined media key event via CGEvent",
+            code: SYSTEM_DEFINED_SWIFT
+                .replace("KEYTYPE", &self.nx_keytype.to_string())
+                .replace("CONSTANT", self.nx_constant),
+        });
+        
+        alternatives
+    }
+}
+
+/// Normalize a media key name for comparison
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| !matches!(c, ' ' | '-' | '_'))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Find a media key by name or alias
+pub fn lookup_media_key(name: &str) -> Option<&'static MediaKey> {
+    MEDIA_KEYS.iter().find(|key| key.matches(name))
+}
+
+/// Find the media key behind a Carbon virtual key code (e.g. 72 for VolumeUp)
+pub fn media_key_for_code(code: u16) -> Option<&'static MediaKey> {
+    MEDIA_KEYS.iter().find(|key| key.key_code == Some(code))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_media_key_lookup() {
+        let play = lookup_media_key("play/pause").unwrap();
+        assert_eq!(play.name, "PlayPause");
+        assert_eq!(play.nx_keytype, 16);
+        assert_eq!(play.key_code, None);
+        assert!(!play.triggerable_by_key_code);
+        
+        assert_eq!(lookup_media_key("Brightness Down").unwrap().nx_keytype, 3);
+        assert_eq!(lookup_media_key("next_track").unwrap().name, "NextTrack");
+        assert!(lookup_media_key("Space").is_none());
+    }
+    
+    #[test]
+    fn test_media_key_for_code() {
+        let volume_up = media_key_for_code(72).unwrap();
+        assert_eq!(volume_up.name, "VolumeUp");
+        assert!(!volume_up.triggerable_by_key_code);
+        
+        // Only a key with a key code can be triggered by it
+        for media in MEDIA_KEYS {
+            assert!(!media.triggerable_by_key_code || media.key_code.is_some(), "{}", media.name);
+        }
+        assert_eq!(media_key_for_code(74).unwrap().nx_constant, "NX_KEYTYPE_MUTE");
+        assert!(media_key_for_code(0).is_none());
+    }
+    
+    #[test]
+    fn test_alternatives() {
+        let mute = lookup_media_key("Mute").unwrap();
+        let alternatives = mute.alternatives();
+        assert_eq!(alternatives[0].language, "applescript");
+        assert!(alternatives[0].code.contains("output muted"));
+        
+        let brightness = lookup_media_key("BrightnessUp").unwrap();
+        let alternatives = brightness.alternatives();
+        assert!(alternatives.iter().all(|alt| alt.language != "applescript"));
+        assert!(alternatives.iter().any(|alt| alt.code.contains("postMediaKey(2)")));
+        assert!(alternatives.iter().any(|alt| alt.code.contains("(2 << 16)")));
+    }
+}
@@ -1,9 +1,39 @@
+pub mod applescript;
+pub mod characters;
 pub mod database;
+pub mod fn_layer;
+pub mod format;
+pub mod gesture;
+pub mod hammerspoon;
+pub mod jxa;
+pub mod karabiner;
+pub mod media_keys;
+pub mod modifiers;
+pub mod notation;
+pub mod output;
 pub mod parser;
+mod phf;
+pub mod python;
+pub mod schema;
 pub mod secondary_functions;
+pub mod sequence;
+pub mod skhd;
+pub mod swift;
+pub mod text;
 pub mod types;
 
-pub use database::{KeyDatabase, KEY_DATABASE};
-pub use parser::{parse_shortcut, KeyCombination, ParseError};
+pub use characters::char_to_keystroke;
+pub use database::{DataSource, KeyDatabase, KEY_DATABASE};
+pub use fn_layer::{fn_layer_key, fn_layer_source};
+pub use format::ShortcutStyle;
+pub use gesture::{is_gesture, parse_gesture, Gesture};
+pub use karabiner::{KarabinerManipulator, KarabinerRule};
+pub use media_keys::{lookup_media_key, media_key_for_code, MediaKey};
+pub use modifiers::Modifiers;
+pub use notation::{detect_notation, parse_any_shortcut, Notation};
+pub use output::OutputFormat;
+pub use parser::{escape_key_name, parse_shortcut, KeyCombination, ParseError};
 pub use secondary_functions::{get_secondary_function, is_secondary_function_query};
-pub use types::{KeyCode, KeyCategory};
\ No newline at end of file
+pub use sequence::{parse_shortcut_sequence, KeySequence};
+pub use text::text_to_keystrokes;
+pub use types::{Confidence, KeyCode, KeyCategory, Layout};
\ No newline at end of file
@@ -0,0 +1,197 @@
+use bitflags::bitflags;
+
+use crate::keycode::{KeyCode, KEY_DATABASE};
+
+bitflags! {
+    /// Modifier keys held in a shortcut
+    ///
+    /// The plain flags (`COMMAND`, `SHIFT`, ...) mean the modifier on either
+    /// side and press the left key; the `_LEFT` and `_RIGHT` flags name one
+    /// physical key. Fn and Caps Lock have a single key each.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+    pub struct Modifiers: u16 {
+        const COMMAND = 1 << 0;
+        const COMMAND_LEFT = 1 << 1;
+        const COMMAND_RIGHT = 1 << 2;
+        const CONTROL = 1 << 3;
+        const CONTROL_LEFT = 1 << 4;
+        const CONTROL_RIGHT = 1 << 5;
+        const OPTION = 1 << 6;
+        const OPTION_LEFT = 1 << 7;
+        const OPTION_RIGHT = 1 << 8;
+        const SHIFT = 1 << 9;
+        const SHIFT_LEFT = 1 << 10;
+        const SHIFT_RIGHT = 1 << 11;
+        const FN = 1 << 12;
+        const CAPS_LOCK = 1 << 13;
+    }
+}
+
+/// Modifier flags and the keys they press, in `to_string` order
+const MODIFIER_KEYS: [(Modifiers, &str); 14] = [
+    (Modifiers::COMMAND, "Command"),
+    (Modifiers::COMMAND_LEFT, "CommandLeft"),
+    (Modifiers::COMMAND_RIGHT, "CommandRight"),
+    (Modifiers::CONTROL, "Control"),
+    (Modifiers::CONTROL_LEFT, "ControlLeft"),
+    (Modifiers::CONTROL_RIGHT, "ControlRight"),
+    (Modifiers::OPTION, "Option"),
+    (Modifiers::OPTION_LEFT, "OptionLeft"),
+    (Modifiers::OPTION_RIGHT, "OptionRight"),
+    (Modifiers::SHIFT, "Shift"),
+    (Modifiers::SHIFT_LEFT, "ShiftLeft"),
+    (Modifiers::SHIFT_RIGHT, "ShiftRight"),
+    (Modifiers::FN, "Fn"),
+    (Modifiers::CAPS_LOCK, "CapsLock"),
+];
+
+impl Modifiers {
+    /// Command on either side
+    pub const ANY_COMMAND: Self = Self::COMMAND.union(Self::COMMAND_LEFT).union(Self::COMMAND_RIGHT);
+    /// Control on either side
+    pub const ANY_CONTROL: Self = Self::CONTROL.union(Self::CONTROL_LEFT).union(Self::CONTROL_RIGHT);
+    /// Option on either side
+    pub const ANY_OPTION: Self = Self::OPTION.union(Self::OPTION_LEFT).union(Self::OPTION_RIGHT);
+    /// Shift on either side
+    pub const ANY_SHIFT: Self = Self::SHIFT.union(Self::SHIFT_LEFT).union(Self::SHIFT_RIGHT);
+    
+    /// The flag of a modifier key, or `None` for other keys
+    pub fn from_key(key: &KeyCode) -> Option<Self> {
+        MODIFIER_KEYS
+            .iter()
+            .find(|(_, name)| *name == key.name)
+            .map(|(flag, _)| *flag)
+    }
+    
+    /// Parse a modifier with a side written before it, such as "Right Cmd" or "left ⌥"
+    pub fn from_sided_name(name: &str) -> Option<Self> {
+        let starts_with = |prefix: &str| {
+            name.get(..prefix.len()).is_some_and(|start| start.eq_ignore_ascii_case(prefix))
+        };
+        let (right, rest) = if starts_with("left") {
+            (false, &name[4..])
+        } else if starts_with("right") {
+            (true, &name[5..])
+        } else {
+            return None;
+        };
+        
+        let plain = Self::from_key(KEY_DATABASE.lookup(rest.trim())?)?;
+        match (plain, right) {
+            (Self::COMMAND, false) => Some(Self::COMMAND_LEFT),
+            (Self::COMMAND, true) => Some(Self::COMMAND_RIGHT),
+            (Self::CONTROL, false) => Some(Self::CONTROL_LEFT),
+            (Self::CONTROL, true) => Some(Self::CONTROL_RIGHT),
+            (Self::OPTION, false) => Some(Self::OPTION_LEFT),
+            (Self::OPTION, true) => Some(Self::OPTION_RIGHT),
+            (Self::SHIFT, false) => Some(Self::SHIFT_LEFT),
+            (Self::SHIFT, true) => Some(Self::SHIFT_RIGHT),
+            _ => None,
+        }
+    }
+    
+    /// Left-side flags as the plain modifier, which presses the same key
+    pub fn canonical(self) -> Self {
+        let mut modifiers = self;
+        for (left, plain) in [
+            (Self::COMMAND_LEFT, Self::COMMAND),
+            (Self::CONTROL_LEFT, Self::CONTROL),
+            (Self::OPTION_LEFT, Self::OPTION),
+            (Self::SHIFT_LEFT, Self::SHIFT),
+        ] {
+            if modifiers.contains(left) {
+                modifiers.remove(left);
+                modifiers.insert(plain);
+            }
+        }
+        modifiers
+    }
+    
+    /// Drop plain flags that a side-specific flag already covers
+    ///
+    /// "Shift+ShiftLeft" holds one key, so it normalizes to `SHIFT_LEFT`.
+    pub fn normalized(self) -> Self {
+        let mut modifiers = self;
+        for (plain, any) in [
+            (Self::COMMAND, Self::ANY_COMMAND),
+            (Self::CONTROL, Self::ANY_CONTROL),
+            (Self::OPTION, Self::ANY_OPTION),
+            (Self::SHIFT, Self::ANY_SHIFT),
+        ] {
+            if self.intersects(any.difference(plain)) {
+                modifiers.remove(plain);
+            }
+        }
+        modifiers
+    }
+    
+    /// Key names of the held modifiers, e.g. `["Command", "ShiftRight"]`
+    pub fn names(self) -> Vec<&'static str> {
+        MODIFIER_KEYS
+            .iter()
+            .filter(|(flag, _)| self.contains(*flag))
+            .map(|(_, name)| *name)
+            .collect()
+    }
+    
+    /// The held modifier keys
+    pub fn keys(self) -> Vec<&'static KeyCode> {
+        self.names()
+            .into_iter()
+            .filter_map(|name| KEY_DATABASE.lookup(name))
+            .collect()
+    }
+    
+    /// Key codes of the held modifiers, without duplicates
+    pub fn codes(self) -> Vec<u16> {
+        let mut codes: Vec<u16> = Vec::new();
+        for key in self.keys() {
+            if !codes.contains(&key.code) {
+                codes.push(key.code);
+            }
+        }
+        codes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_every_flag_has_a_key() {
+        for (flag, name) in MODIFIER_KEYS {
+            let key = KEY_DATABASE.lookup(name).unwrap_or_else(|| panic!("missing key {}", name));
+            assert_eq!(Modifiers::from_key(key), Some(flag));
+        }
+        assert_eq!(Modifiers::all().keys().len(), MODIFIER_KEYS.len());
+    }
+    
+    #[test]
+    fn test_sides() {
+        let modifiers = Modifiers::SHIFT_RIGHT | Modifiers::COMMAND;
+        assert_eq!(modifiers.names(), vec!["Command", "ShiftRight"]);
+        assert_eq!(modifiers.codes(), vec![55, 60]);
+        assert!(modifiers.intersects(Modifiers::ANY_SHIFT));
+        assert!(!modifiers.contains(Modifiers::SHIFT));
+    }
+    
+    #[test]
+    fn test_from_sided_name() {
+        assert_eq!(Modifiers::from_sided_name("Right Cmd"), Some(Modifiers::COMMAND_RIGHT));
+        assert_eq!(Modifiers::from_sided_name("left⌥"), Some(Modifiers::OPTION_LEFT));
+        assert_eq!(Modifiers::from_sided_name("Right Ctrl"), Some(Modifiers::CONTROL_RIGHT));
+        assert_eq!(Modifiers::from_sided_name("Left Fn"), None);
+        assert_eq!(Modifiers::from_sided_name("Right Arrow"), None);
+    }
+    
+    #[test]
+    fn test_normalized() {
+        let modifiers = Modifiers::SHIFT | Modifiers::SHIFT_LEFT | Modifiers::OPTION;
+        assert_eq!(modifiers.normalized(), Modifiers::SHIFT_LEFT | Modifiers::OPTION);
+        assert_eq!(modifiers.normalized().codes(), vec![58, 56]);
+        
+        let both = Modifiers::SHIFT_LEFT | Modifiers::SHIFT_RIGHT;
+        assert_eq!(both.normalized(), both);
+    }
+}
@@ -0,0 +1,638 @@
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumString};
+
+use std::ops::Range;
+
+use crate::keycode::parser::{parse_combination, trimmed_span, word_spans};
+use crate::keycode::{escape_key_name, parse_shortcut_sequence, KeyCombination, KeySequence, ParseError};
+
+/// Shortcut notations used by editors, frameworks and menus
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Display, EnumString)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum Notation {
+    /// This crate's own syntax: "Cmd+Shift+A", "⌘⇧A"
+    Native,
+    /// Emacs key descriptions: "C-x C-s", "M-f", "s-a"
+    Emacs,
+    /// Vim key notation: "<D-S-a>", "<C-w>j"
+    Vim,
+    /// VS Code keybindings: "cmd+k cmd+s"
+    VsCode,
+    /// Electron accelerators: "CommandOrControl+Shift+Z"
+    Electron,
+    /// Cocoa key equivalents as used by NSMenuItem: "@$k"
+    Cocoa,
+}
+
+impl Notation {
+    /// All known notations
+    pub const fn all() -> &'static [Notation] {
+        &[
+            Notation::Native,
+            Notation::Emacs,
+            Notation::Vim,
+            Notation::VsCode,
+            Notation::Electron,
+            Notation::Cocoa,
+        ]
+    }
+}
+
+/// Modifier prefixes of Emacs key descriptions (Meta and Alt are Option on a Mac)
+const EMACS_MODIFIERS: [(char, &str); 5] = [
+    ('C', "Control"),
+    ('M', "Option"),
+    ('s', "Command"),
+    ('S', "Shift"),
+    ('A', "Option"),
+];
+
+/// Modifier prefixes inside Vim `<...>` keys
+const VIM_MODIFIERS: [(char, &str); 5] = [
+    ('C', "Control"),
+    ('S', "Shift"),
+    ('M', "Option"),
+    ('A', "Option"),
+    ('D', "Command"),
+];
+
+/// Modifier characters of Cocoa key equivalents; `#` marks a keypad key
+const COCOA_MODIFIERS: [(char, &str); 4] = [
+    ('@', "Command"),
+    ('$', "Shift"),
+    ('^', "Control"),
+    ('~', "Option"),
+];
+
+/// Emacs key names, matched case-sensitively ("DEL" is backspace, "<delete>" is forward delete)
+const EMACS_KEYS: &[(&str, &str)] = &[
+    ("RET", "Return"),
+    ("SPC", "Space"),
+    ("TAB", "Tab"),
+    ("ESC", "Escape"),
+    ("DEL", "Delete"),
+    ("<return>", "Return"),
+    ("<backspace>", "Delete"),
+    ("<delete>", "ForwardDelete"),
+    ("<prior>", "PageUp"),
+    ("<next>", "PageDown"),
+    ("<kp-add>", "NumpadPlus"),
+    ("<kp-subtract>", "NumpadMinus"),
+    ("<kp-multiply>", "NumpadMultiply"),
+    ("<kp-divide>", "NumpadDivide"),
+    ("<kp-decimal>", "NumpadDecimal"),
+    ("<kp-enter>", "NumpadEnter"),
+    ("<kp-equal>", "NumpadEquals"),
+];
+
+/// Vim key names, matched ignoring case
+const VIM_KEYS: &[(&str, &str)] = &[
+    ("CR", "Return"),
+    ("BS", "Delete"),
+    ("Del", "ForwardDelete"),
+    ("Insert", "Help"),
+    ("lt", "<"),
+    ("Bar", "|"),
+    ("Bslash", "\\"),
+    ("kPlus", "NumpadPlus"),
+    ("kMinus", "NumpadMinus"),
+    ("kMultiply", "NumpadMultiply"),
+    ("kDivide", "NumpadDivide"),
+    ("kPoint", "NumpadDecimal"),
+    ("kEnter", "NumpadEnter"),
+];
+
+/// VS Code and Electron names, matched ignoring case
+const ACCELERATOR_KEYS: &[(&str, &str)] = &[
+    ("CommandOrControl", "Command"),
+    ("CmdOrCtrl", "Command"),
+    ("Meta", "Command"),
+    ("Super", "Command"),
+    ("AltGr", "OptionRight"),
+    ("Plus", "+"),
+    ("Delete", "ForwardDelete"),
+    ("Insert", "Help"),
+    ("VolumeMute", "Mute"),
+    ("numadd", "NumpadPlus"),
+    ("numsub", "NumpadMinus"),
+    ("nummult", "NumpadMultiply"),
+    ("numdiv", "NumpadDivide"),
+    ("numdec", "NumpadDecimal"),
+    ("numpad_add", "NumpadPlus"),
+    ("numpad_subtract", "NumpadMinus"),
+    ("numpad_multiply", "NumpadMultiply"),
+    ("numpad_divide", "NumpadDivide"),
+    ("numpad_decimal", "NumpadDecimal"),
+];
+
+/// Keys named only by Electron accelerators
+const ELECTRON_ONLY: [&str; 5] = ["commandorcontrol", "cmdorctrl", "super", "plus", "altgr"];
+
+/// Keys named only by VS Code keybindings
+const VSCODE_ONLY: [&str; 6] = [
+    "meta",
+    "numpad_add",
+    "numpad_subtract",
+    "numpad_multiply",
+    "numpad_divide",
+    "numpad_decimal",
+];
+
+/// Guess the notation of a shortcut
+///
+/// Checks the distinctive syntax of each notation in turn ("C-x" prefixes for
+/// Emacs, `<...>` keys for Vim, modifier characters for Cocoa, accelerator
+/// names for Electron and VS Code) and falls back to the native syntax.
+/// Only syntax the native parser would not accept counts: "A-B", "^C" and
+/// "ctrl+alt+delete" stay native.
+pub fn detect_notation(shortcut: &str) -> Notation {
+    let shortcut = shortcut.trim();
+    let words: Vec<&str> = shortcut.split_whitespace().collect();
+    
+    if words.iter().any(|word| is_emacs_chord(word)) {
+        return Notation::Emacs;
+    }
+    if is_vim_sequence(shortcut) {
+        return Notation::Vim;
+    }
+    if is_cocoa_equivalent(shortcut) {
+        return Notation::Cocoa;
+    }
+    
+    let parts: Vec<String> = words
+        .iter()
+        .flat_map(|word| word.split('+'))
+        .map(str::to_lowercase)
+        .collect();
+    if parts.iter().any(|part| ELECTRON_ONLY.contains(&part.as_str()) || is_electron_numpad(part)) {
+        return Notation::Electron;
+    }
+    if parts.iter().any(|part| VSCODE_ONLY.contains(&part.as_str())) {
+        return Notation::VsCode;
+    }
+    
+    Notation::Native
+}
+
+/// Parse a shortcut written in the given notation
+pub fn parse_shortcut_notation(shortcut: &str, notation: Notation) -> Result<KeySequence, ParseError> {
+    if shortcut.trim().is_empty() {
+        return Err(ParseError::EmptyShortcut);
+    }
+    
+    let chords = match notation {
+        Notation::Native => return parse_shortcut_sequence(shortcut),
+        Notation::Emacs => word_chords(shortcut, emacs_chord)?,
+        Notation::Vim => vim_chords(shortcut)?,
+        Notation::VsCode => word_chords(shortcut, accelerator_chord)?,
+        Notation::Electron => {
+            let span = trimmed_span(shortcut);
+            let names = accelerator_chord(&shortcut[span.clone()]).map_err(|e| e.offset(span.start))?;
+            vec![(names, span)]
+        }
+        Notation::Cocoa => vec![(cocoa_chord(shortcut)?, 0..shortcut.len())],
+    };
+    
+    // Spans of errors in the translated text are widened to the whole chord
+    let chords = chords
+        .into_iter()
+        .map(|(names, span)| combine(&names).map_err(|e| e.with_span(span).with_suggestions()))
+        .collect::<Result<Vec<_>, _>>()?;
+    
+    Ok(KeySequence { chords, delay: None })
+}
+
+/// Parse a shortcut in the given notation, or in the detected one when `None`
+///
+/// A detected notation is only a guess, so when the shortcut does not parse
+/// in it the native syntax is tried before reporting the first error.
+pub fn parse_any_shortcut(
+    shortcut: &str,
+    notation: Option<Notation>,
+) -> Result<(Notation, KeySequence), ParseError> {
+    if let Some(notation) = notation {
+        return parse_shortcut_notation(shortcut, notation).map(|sequence| (notation, sequence));
+    }
+    
+    let detected = detect_notation(shortcut);
+    match parse_shortcut_notation(shortcut, detected) {
+        Ok(sequence) => Ok((detected, sequence)),
+        Err(e) if detected != Notation::Native => parse_shortcut_sequence(shortcut)
+            .map(|sequence| (Notation::Native, sequence))
+            .map_err(|_| e),
+        Err(e) => Err(e),
+    }
+}
+
+/// Native key names of one chord and where the chord was written
+type Chord = (Vec<String>, Range<usize>);
+
+/// Translate each whitespace-separated word as one chord
+fn word_chords(
+    shortcut: &str,
+    chord: fn(&str) -> Result<Vec<String>, ParseError>,
+) -> Result<Vec<Chord>, ParseError> {
+    word_spans(shortcut)
+        .into_iter()
+        .map(|span| {
+            chord(&shortcut[span.clone()])
+                .map(|names| (names, span.clone()))
+                .map_err(|e| e.offset(span.start))
+        })
+        .collect()
+}
+
+/// Build a combination from native key names
+fn combine(names: &[String]) -> Result<KeyCombination, ParseError> {
+    let escaped: Vec<String> = names.iter().map(|name| escape_key_name(name)).collect();
+    parse_combination(&escaped.join("+"))
+}
+
+/// Key names for a single character key, adding Shift for upper-case letters
+fn char_key(ch: char, names: &mut Vec<String>) {
+    if ch.is_ascii_uppercase() {
+        names.push("Shift".to_string());
+    }
+    names.push(ch.to_string());
+}
+
+/// Look up a notation-specific key name
+fn translate(name: &str, table: &[(&str, &'static str)], ignore_case: bool) -> Option<&'static str> {
+    table
+        .iter()
+        .find(|(alias, _)| if ignore_case { alias.eq_ignore_ascii_case(name) } else { *alias == name })
+        .map(|(_, native)| *native)
+}
+
+/// Check for an Emacs chord such as "C-x", "M-<f5>" or "s-a"
+///
+/// The key after the prefixes must look like Emacs too: a lower-case letter,
+/// a non-letter character, an Emacs key name or a `<name>` key. "A-B" is a
+/// native shortcut, not Alt+Shift+B.
+fn is_emacs_chord(word: &str) -> bool {
+    let mut rest = word;
+    let mut prefixed = false;
+    while rest.len() > 2 && rest.as_bytes()[1] == b'-' {
+        let prefix = rest.chars().next().unwrap_or_default();
+        if prefix != 'H' && !EMACS_MODIFIERS.iter().any(|(c, _)| *c == prefix) {
+            break;
+        }
+        prefixed = true;
+        rest = &rest[2..];
+    }
+    
+    let mut chars = rest.chars();
+    let emacs_key = match (chars.next(), chars.next()) {
+        (Some(key), None) => !key.is_uppercase(),
+        _ => translate(rest, EMACS_KEYS, false).is_some() || (rest.starts_with('<') && rest.ends_with('>')),
+    };
+    prefixed && emacs_key
+}
+
+/// Check for Vim `<...>` keys with a name or a modifier prefix inside
+fn is_vim_sequence(shortcut: &str) -> bool {
+    let mut rest = shortcut;
+    while let Some(start) = rest.find('<') {
+        let after = &rest[start + 1..];
+        match after.find('>') {
+            Some(end) if end >= 2 && !after[..end].contains(char::is_whitespace) => return true,
+            Some(_) => rest = after,
+            None => return false,
+        }
+    }
+    false
+}
+
+/// Check for Cocoa modifier characters followed by a single key character
+///
+/// Cocoa writes unshifted letters in lower case, so an upper-case key after
+/// the modifiers ("^C") is read as native Control+C rather than Control+Shift+C.
+fn is_cocoa_equivalent(shortcut: &str) -> bool {
+    let chars: Vec<char> = shortcut.chars().collect();
+    let is_modifier = |c: &char| *c == '#' || COCOA_MODIFIERS.iter().any(|(m, _)| m == c);
+    
+    match chars.split_last() {
+        Some((key, modifiers)) => {
+            (!modifiers.is_empty() && modifiers.iter().all(is_modifier) && !key.is_uppercase())
+                || cocoa_function_key(*key).is_some() && modifiers.iter().all(is_modifier)
+        }
+        None => false,
+    }
+}
+
+/// Check for Electron's "num0".."num9" keypad names
+fn is_electron_numpad(part: &str) -> bool {
+    part.len() == 4 && part.starts_with("num") && part.as_bytes()[3].is_ascii_digit()
+}
+
+/// Key names of one Emacs chord: "C-M-x", "S-<f5>", "C--"
+fn emacs_chord(word: &str) -> Result<Vec<String>, ParseError> {
+    let mut names = Vec::new();
+    let mut rest = word;
+    
+    // A prefix needs something after its dash, so "C--" is Control and "-"
+    while rest.len() > 2 && rest.as_bytes()[1] == b'-' {
+        let prefix = rest.chars().next().unwrap_or_default();
+        match EMACS_MODIFIERS.iter().find(|(c, _)| *c == prefix) {
+            Some((_, modifier)) => names.push(modifier.to_string()),
+            None if prefix == 'H' => {
+                let at = word.len() - rest.len();
+                return Err(ParseError::unknown_key("Hyper (H-)", at..at + 2));
+            }
+            None => break,
+        }
+        rest = &rest[2..];
+    }
+    
+    if let Some(native) = translate(rest, EMACS_KEYS, false) {
+        names.push(native.to_string());
+    } else if let Some(name) = rest.strip_prefix('<').and_then(|r| r.strip_suffix('>')) {
+        names.push(name.strip_prefix("kp-").map_or(name.to_string(), |n| format!("Numpad{}", n)));
+    } else {
+        let mut chars = rest.chars();
+        match (chars.next(), chars.next()) {
+            (Some(ch), None) => char_key(ch, &mut names),
+            _ => names.push(rest.to_string()),
+        }
+    }
+    
+    Ok(names)
+}
+
+/// Chords of a Vim key sequence: "<D-S-a>", "<C-w>j", "<lt>"
+fn vim_chords(shortcut: &str) -> Result<Vec<Chord>, ParseError> {
+    let mut chords = Vec::new();
+    let mut rest = shortcut;
+    
+    while let Some(ch) = rest.chars().next() {
+        let at = shortcut.len() - rest.len();
+        let bracketed = rest
+            .strip_prefix('<')
+            .and_then(|after| after.find('>').filter(|&end| end >= 2).map(|end| &after[..end]));
+        
+        match bracketed {
+            Some(inner) => {
+                let span = at..at + inner.len() + 2;
+                chords.push((vim_key(inner).map_err(|e| e.offset(at))?, span));
+                rest = &rest[inner.len() + 2..];
+            }
+            None => {
+                if !ch.is_whitespace() {
+                    let mut names = Vec::new();
+                    char_key(ch, &mut names);
+                    chords.push((names, at..at + ch.len_utf8()));
+                }
+                rest = &rest[ch.len_utf8()..];
+            }
+        }
+    }
+    
+    if chords.is_empty() {
+        return Err(ParseError::EmptyShortcut);
+    }
+    Ok(chords)
+}
+
+/// Key names of the inside of a Vim `<...>` key: "D-S-a", "CR", "C-lt"
+fn vim_key(inner: &str) -> Result<Vec<String>, ParseError> {
+    let mut names = Vec::new();
+    let mut rest = inner;
+    
+    while rest.len() > 2 && rest.as_bytes()[1] == b'-' {
+        let prefix = rest.chars().next().unwrap_or_default().to_ascii_uppercase();
+        match VIM_MODIFIERS.iter().find(|(c, _)| *c == prefix) {
+            Some((_, modifier)) => names.push(modifier.to_string()),
+            None => return Err(ParseError::unknown_key(format!("<{}>", inner), 0..inner.len() + 2)),
+        }
+        rest = &rest[2..];
+    }
+    
+    // Letters inside <...> ignore case; Shift must be written as S-
+    let keypad_digit = rest.strip_prefix('k').filter(|d| d.len() == 1 && d.as_bytes()[0].is_ascii_digit());
+    names.push(match (translate(rest, VIM_KEYS, true), keypad_digit) {
+        (Some(native), _) => native.to_string(),
+        (None, Some(digit)) => format!("Numpad{}", digit),
+        (None, None) => rest.to_string(),
+    });
+    
+    Ok(names)
+}
+
+/// Key names of one VS Code or Electron chord: "cmd+k", "CmdOrCtrl+Plus"
+fn accelerator_chord(chord: &str) -> Result<Vec<String>, ParseError> {
+    let mut parts: Vec<&str> = chord.split('+').collect();
+    
+    // "ctrl++" ends in the plus key rather than an empty name
+    if parts.len() > 1 && parts.last() == Some(&"") {
+        parts.pop();
+        if parts.last() == Some(&"") {
+            parts.pop();
+        }
+        parts.push("+");
+    }
+    
+    parts
+        .into_iter()
+        .map(|part| {
+            let part = part.trim();
+            if part.is_empty() {
+                return Err(ParseError::InvalidFormat);
+            }
+            if let Some(native) = translate(part, ACCELERATOR_KEYS, true) {
+                return Ok(native.to_string());
+            }
+            let lower = part.to_lowercase();
+            Ok(if is_electron_numpad(&lower) {
+                format!("Numpad{}", &lower[3..])
+            } else {
+                part.to_string()
+            })
+        })
+        .collect()
+}
+
+/// Key names of a Cocoa key equivalent: "@$k", "^~\u{F700}", "#5"
+fn cocoa_chord(shortcut: &str) -> Result<Vec<String>, ParseError> {
+    let chars: Vec<char> = shortcut.chars().collect();
+    let (&key, modifiers) = chars.split_last().ok_or(ParseError::EmptyShortcut)?;
+    
+    let mut names = Vec::new();
+    let mut keypad = false;
+    for ch in modifiers {
+        match COCOA_MODIFIERS.iter().find(|(c, _)| c == ch) {
+            Some((_, modifier)) => names.push(modifier.to_string()),
+            None if *ch == '#' => keypad = true,
+            None => return Err(ParseError::InvalidFormat),
+        }
+    }
+    
+    if let Some(function_key) = cocoa_function_key(key) {
+        names.push(function_key);
+    } else if keypad {
+        let name = cocoa_keypad_key(key)
+            .ok_or_else(|| ParseError::unknown_key(format!("#{}", key), 0..shortcut.len()))?;
+        names.push(name);
+    } else {
+        match key {
+            '\r' => names.push("Return".to_string()),
+            '\t' => names.push("Tab".to_string()),
+            '\u{1b}' => names.push("Escape".to_string()),
+            '\u{8}' | '\u{7f}' => names.push("Delete".to_string()),
+            ' ' => names.push("Space".to_string()),
+            ch => char_key(ch, &mut names),
+        }
+    }
+    
+    Ok(names)
+}
+
+/// Native name of an `NS*FunctionKey` character
+fn cocoa_function_key(ch: char) -> Option<String> {
+    let name = match ch as u32 {
+        0xF700 => "UpArrow",
+        0xF701 => "DownArrow",
+        0xF702 => "LeftArrow",
+        0xF703 => "RightArrow",
+        code @ 0xF704..=0xF717 => return Some(format!("F{}", code - 0xF704 + 1)),
+        0xF727 => "Help",
+        0xF728 => "ForwardDelete",
+        0xF729 => "Home",
+        0xF72B => "End",
+        0xF72C => "PageUp",
+        0xF72D => "PageDown",
+        0xF739 => "Clear",
+        0xF746 => "Help",
+        _ => return None,
+    };
+    Some(name.to_string())
+}
+
+/// Native name of a keypad key marked with `#`
+fn cocoa_keypad_key(ch: char) -> Option<String> {
+    let name = match ch {
+        '0'..='9' => return Some(format!("Numpad{}", ch)),
+        '+' => "NumpadPlus",
+        '-' => "NumpadMinus",
+        '*' => "NumpadMultiply",
+        '/' => "NumpadDivide",
+        '.' => "NumpadDecimal",
+        '=' => "NumpadEquals",
+        '\r' | '\u{3}' => "NumpadEnter",
+        _ => return None,
+    };
+    Some(name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn parse(shortcut: &str, notation: Notation) -> String {
+        parse_shortcut_notation(shortcut, notation)
+            .unwrap_or_else(|e| panic!("{} failed: {}", shortcut, e))
+            .to_string()
+    }
+    
+    #[test]
+    fn test_emacs_notation() {
+        assert_eq!(parse("C-x C-s", Notation::Emacs), "Control+X Control+S");
+        assert_eq!(parse("M-f", Notation::Emacs), "Option+F");
+        assert_eq!(parse("s-a", Notation::Emacs), "Command+A");
+        assert_eq!(parse("C-M-S-<f5>", Notation::Emacs), "Control+Option+Shift+F5");
+        assert_eq!(parse("C-X", Notation::Emacs), "Control+Shift+X");
+        assert_eq!(parse("C--", Notation::Emacs), "Control+Minus");
+        assert_eq!(parse("C-x RET", Notation::Emacs), "Control+X Return");
+        assert_eq!(parse("<delete> DEL", Notation::Emacs), "ForwardDelete Delete");
+        
+        let err = parse_shortcut_notation("C-x H-a", Notation::Emacs).unwrap_err();
+        assert_eq!(err.span(), Some(4..6));
+    }
+    
+    #[test]
+    fn test_vim_notation() {
+        assert_eq!(parse("<D-S-a>", Notation::Vim), "Command+Shift+A");
+        assert_eq!(parse("<C-w>j", Notation::Vim), "Control+W J");
+        assert_eq!(parse("<C-w>J", Notation::Vim), "Control+W Shift+J");
+        assert_eq!(parse("<M-CR>", Notation::Vim), "Option+Return");
+        assert_eq!(parse("<C-lt>", Notation::Vim), "Control+Shift+Comma");
+        assert_eq!(parse("<k5><Del>", Notation::Vim), "Numpad5 ForwardDelete");
+        
+        let err = parse_shortcut_notation("j<X-a>", Notation::Vim).unwrap_err();
+        assert_eq!(err.span(), Some(1..6));
+        let err = parse_shortcut_notation("<C-w><Escp>", Notation::Vim).unwrap_err();
+        assert_eq!(err.span(), Some(5..11));
+        assert!(!err.suggestions().is_empty());
+    }
+    
+    #[test]
+    fn test_accelerator_notations() {
+        assert_eq!(parse("cmd+k cmd+s", Notation::VsCode), "Command+K Command+S");
+        assert_eq!(parse("meta+shift+p", Notation::VsCode), "Command+Shift+P");
+        assert_eq!(parse("ctrl+-", Notation::VsCode), "Control+Minus");
+        assert_eq!(parse("CommandOrControl+Shift+Z", Notation::Electron), "Command+Shift+Z");
+        assert_eq!(parse("CmdOrCtrl+Plus", Notation::Electron), "Command+Shift+Equal");
+        assert_eq!(parse("Alt+num7", Notation::Electron), "Option+Numpad7");
+    }
+    
+    #[test]
+    fn test_cocoa_notation() {
+        assert_eq!(parse("@$k", Notation::Cocoa), "Command+Shift+K");
+        assert_eq!(parse("@K", Notation::Cocoa), "Command+Shift+K");
+        assert_eq!(parse("^~\u{F700}", Notation::Cocoa), "Control+Option+UpArrow");
+        assert_eq!(parse("@\u{F709}", Notation::Cocoa), "Command+F6");
+        assert_eq!(parse("@#+", Notation::Cocoa), "Command+NumpadPlus");
+        assert_eq!(parse("@,", Notation::Cocoa), "Command+Comma");
+    }
+    
+    #[test]
+    fn test_detect_notation() {
+        assert_eq!(detect_notation("Cmd+Shift+A"), Notation::Native);
+        assert_eq!(detect_notation("⌘⇧A"), Notation::Native);
+        assert_eq!(detect_notation("Cmd+- Cmd+<"), Notation::Native);
+        assert_eq!(detect_notation("C-x k"), Notation::Emacs);
+        assert_eq!(detect_notation("<D-S-a>"), Notation::Vim);
+        assert_eq!(detect_notation("meta+k meta+s"), Notation::VsCode);
+        assert_eq!(detect_notation("CommandOrControl+Shift+Z"), Notation::Electron);
+        assert_eq!(detect_notation("@$k"), Notation::Cocoa);
+        
+        let (notation, sequence) = parse_any_shortcut("C-x C-s", None).unwrap();
+        assert_eq!(notation, Notation::Emacs);
+        assert_eq!(sequence.chords.len(), 2);
+        
+        assert_eq!("vscode".parse::<Notation>().unwrap(), Notation::VsCode);
+    }
+    
+    #[test]
+    fn test_native_input_stays_native() {
+        // Lower case alone is not VS Code, where "delete" would be ForwardDelete
+        for shortcut in ["ctrl+alt+delete", "right cmd+a", "cmd + a", "cmd+page up", "cmd+k cmd+s", "A-B", "^C"] {
+            assert_eq!(detect_notation(shortcut), Notation::Native, "{}", shortcut);
+        }
+        
+        let parse = |shortcut: &str| parse_any_shortcut(shortcut, None).unwrap().1;
+        assert_eq!(parse("ctrl+alt+delete").to_keycodes(), vec![vec![59, 58, 51]]);
+        assert_eq!(parse("right cmd+a").to_string(), "CommandRight+A");
+        assert_eq!(parse("cmd + a").to_string(), "Command+A");
+        assert_eq!(parse("cmd+page up").to_string(), "Command+PageUp");
+        
+        // Emacs and Cocoa need their own markers
+        assert_eq!(detect_notation("C-M-x"), Notation::Emacs);
+        assert_eq!(detect_notation("S-<f5>"), Notation::Emacs);
+        assert_eq!(detect_notation("^c"), Notation::Cocoa);
+        // Native errors rather than a silent Option+Shift+B or Control+Shift+C
+        assert_eq!(parse_any_shortcut("A-B", None).unwrap_err(), parse_shortcut_sequence("A-B").unwrap_err());
+        assert_eq!(parse_any_shortcut("^C", None).unwrap_err(), parse_shortcut_sequence("^C").unwrap_err());
+    }
+    
+    #[test]
+    fn test_detected_notation_falls_back_to_native() {
+        // "meta" is VS Code's; neither parser knows "Page Up" there, so VS Code's error is kept
+        let err = parse_any_shortcut("meta+Page Up", None).unwrap_err();
+        assert_eq!(err, parse_shortcut_notation("meta+Page Up", Notation::VsCode).unwrap_err());
+        
+        // An explicit notation is never second-guessed
+        let err = parse_any_shortcut("cmd+page up", Some(Notation::VsCode)).unwrap_err();
+        assert!(matches!(err, ParseError::UnknownKey { .. }));
+    }
+}
@@ -0,0 +1,159 @@
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumString};
+
+use crate::keycode::{KeyCombination, KeySequence, Modifiers};
+
+/// Modifier flags that set one event flag, e.g. Command and CommandRight both set the Command flag
+pub(crate) const FLAG_GROUPS: [Modifiers; 6] = [
+    Modifiers::ANY_COMMAND,
+    Modifiers::ANY_SHIFT,
+    Modifiers::ANY_OPTION,
+    Modifiers::ANY_CONTROL,
+    Modifiers::FN,
+    Modifiers::CAPS_LOCK,
+];
+
+/// One posted key event: the key code, whether it goes down, and the flag groups held
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct PostedEvent {
+    pub code: u16,
+    pub down: bool,
+    pub flags: Vec<Modifiers>,
+}
+
+/// Languages a shortcut's key presses can be generated in
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Display, EnumString)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum OutputFormat {
+    /// System Events AppleScript: `key code 0 using {command down}`
+    AppleScript,
+    /// JavaScript for Automation: `Application('System Events').keyCode(0, {using: 'command down'})`
+    Jxa,
+    /// Swift posting `CGEvent` key down/up pairs with `.maskCommand` and friends
+    Swift,
+    /// Python posting the same events through PyObjC's `Quartz.CGEventCreateKeyboardEvent`
+    Python,
+    /// Python calling `pyautogui.hotkey('command', 'shift', 'a')`
+    PyAutoGui,
+    /// Hammerspoon Lua calling `hs.eventtap.keyStroke({"cmd", "shift"}, "a")`
+    Hammerspoon,
+}
+
+impl OutputFormat {
+    /// All known output formats
+    pub const fn all() -> &'static [OutputFormat] {
+        &[
+            OutputFormat::AppleScript,
+            OutputFormat::Jxa,
+            OutputFormat::Swift,
+            OutputFormat::Python,
+            OutputFormat::PyAutoGui,
+            OutputFormat::Hammerspoon,
+        ]
+    }
+}
+
+impl KeySequence {
+    /// Code pressing the sequence in the given format
+    ///
+    /// None when the format cannot press one of the keys: a key pyautogui has
+    /// no name for, or Caps Lock held as a modifier in Hammerspoon.
+    pub fn to_script(&self, format: OutputFormat) -> Option<String> {
+        match format {
+            OutputFormat::AppleScript => Some(self.to_applescript()),
+            OutputFormat::Jxa => Some(self.to_jxa()),
+            OutputFormat::Swift => Some(self.to_swift()),
+            OutputFormat::Python => Some(self.to_python()),
+            OutputFormat::PyAutoGui => self.to_pyautogui(),
+            OutputFormat::Hammerspoon => self.to_hammerspoon(),
+        }
+    }
+}
+
+impl KeyCombination {
+    /// Code pressing the combination in the given format
+    pub fn to_script(&self, format: OutputFormat) -> Option<String> {
+        KeySequence::from(self.clone()).to_script(format)
+    }
+    
+    /// Key events pressing each modifier, the key, then releasing the modifiers in reverse
+    ///
+    /// A modifier's own key-down event already carries its flag and its
+    /// key-up event no longer does, as macOS reports them. Both keys of a
+    /// side pair set one flag, which stays until the last is released.
+    pub(crate) fn posted_events(&self) -> Vec<PostedEvent> {
+        let held: Vec<(u16, Modifiers)> = self.modifiers
+            .keys()
+            .into_iter()
+            .filter_map(|key| {
+                let flag = Modifiers::from_key(key)?;
+                FLAG_GROUPS.iter().find(|group| group.intersects(flag)).map(|group| (key.code, *group))
+            })
+            .collect();
+        let flags = |count: usize| {
+            let mut groups: Vec<Modifiers> = Vec::new();
+            for (_, group) in &held[..count] {
+                if !groups.contains(group) {
+                    groups.push(*group);
+                }
+            }
+            groups
+        };
+        let event = |code: u16, down: bool, count: usize| PostedEvent { code, down, flags: flags(count) };
+        
+        let mut events = Vec::new();
+        for (i, (code, _)) in held.iter().enumerate() {
+            events.push(event(*code, true, i + 1));
+        }
+        events.push(event(self.key.code, true, held.len()));
+        events.push(event(self.key.code, false, held.len()));
+        for (i, (code, _)) in held.iter().enumerate().rev() {
+            events.push(event(*code, false, i));
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keycode::parse_shortcut;
+    
+    #[test]
+    fn test_output_format_names() {
+        assert_eq!("applescript".parse::<OutputFormat>().unwrap(), OutputFormat::AppleScript);
+        assert_eq!("JXA".parse::<OutputFormat>().unwrap(), OutputFormat::Jxa);
+        assert_eq!(serde_json::to_value(OutputFormat::AppleScript).unwrap(), "applescript");
+        assert_eq!("pyautogui".parse::<OutputFormat>().unwrap(), OutputFormat::PyAutoGui);
+        assert_eq!(serde_json::to_value(OutputFormat::PyAutoGui).unwrap(), "pyautogui");
+    }
+    
+    #[test]
+    fn test_posted_events() {
+        let events = parse_shortcut("ShiftLeft+ShiftRight+F1").unwrap().posted_events();
+        let shift = vec![Modifiers::ANY_SHIFT];
+        assert_eq!(
+            events,
+            vec![
+                PostedEvent { code: 56, down: true, flags: shift.clone() },
+                PostedEvent { code: 60, down: true, flags: shift.clone() },
+                PostedEvent { code: 122, down: true, flags: shift.clone() },
+                PostedEvent { code: 122, down: false, flags: shift.clone() },
+                PostedEvent { code: 60, down: false, flags: shift },
+                PostedEvent { code: 56, down: false, flags: vec![] },
+            ]
+        );
+    }
+    
+    #[test]
+    fn test_every_format_generates() {
+        let combination = parse_shortcut("Cmd+Shift+Z").unwrap();
+        for format in OutputFormat::all() {
+            // Key code 6, or the key's name for pyautogui and Hammerspoon
+            let script = combination.to_script(*format).unwrap();
+            assert!(script.contains('6') || script.contains("'z'") || script.contains("\"z\""), "{}: {}", format, script);
+        }
+        assert!(parse_shortcut("Section").unwrap().to_script(OutputFormat::PyAutoGui).is_none());
+    }
+}
@@ -1,67 +1,255 @@
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
+use std::ops::Range;
 use thiserror::Error;
-use crate::keycode::{KeyCode, KeyCategory, KEY_DATABASE};
+use crate::keycode::{fn_layer_key, fn_layer_source, KeyCode, Modifiers, KEY_DATABASE};
+use crate::search::FuzzySearcher;
 
 /// Represents a keyboard shortcut combination
-#[derive(Debug, Clone, PartialEq)]
+///
+/// Combinations compare, hash and sort by the keys they press, so
+/// "Shift+Cmd+A" equals "Cmd+Shift+A" (see `canonical`).
+#[derive(Debug, Clone)]
 pub struct KeyCombination {
-    /// Modifier keys in the combination (Command, Shift, Option, Control)
-    pub modifiers: Vec<KeyCode>,
+    /// Modifier keys held with the main key, by side where the shortcut named one
+    pub modifiers: Modifiers,
     /// The main key (non-modifier)
     pub key: KeyCode,
+    /// Shifted symbol the shortcut named (e.g. `{`), typed as Shift plus `key`
+    pub shifted_symbol: Option<String>,
+    /// Key the shortcut pressed with Fn to get `key` (e.g. LeftArrow for Home)
+    pub fn_key: Option<KeyCode>,
+}
+
+/// Combinations are equal when they press the same keys, however they were written
+impl PartialEq for KeyCombination {
+    fn eq(&self, other: &Self) -> bool {
+        self.identity() == other.identity()
+    }
+}
+
+impl Eq for KeyCombination {}
+
+impl Hash for KeyCombination {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.identity().hash(state);
+    }
+}
+
+/// Sorted by main key code, then by modifiers
+impl Ord for KeyCombination {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.identity().cmp(&other.identity())
+    }
+}
+
+impl PartialOrd for KeyCombination {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
 }
 
 /// Errors that can occur during shortcut parsing
-#[derive(Error, Debug)]
+///
+/// Spans are byte ranges into the string that was parsed.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
 pub enum ParseError {
     #[error("Empty shortcut string")]
     EmptyShortcut,
     
-    #[error("Unknown key: {0}")]
-    UnknownKey(String),
+    #[error("Unknown key: {key}")]
+    UnknownKey {
+        key: String,
+        span: Range<usize>,
+        /// Key names the input was probably meant to be, best first
+        suggestions: Vec<String>,
+    },
     
     #[error("Multiple non-modifier keys in shortcut")]
-    MultipleMainKeys,
+    MultipleMainKeys {
+        /// The second non-modifier key
+        span: Range<usize>,
+    },
     
     #[error("No main key in shortcut (only modifiers)")]
-    NoMainKey,
+    NoMainKey {
+        span: Range<usize>,
+    },
+    
+    #[error("Not a modifier: {key} (gestures only press modifiers)")]
+    NotAModifier {
+        key: String,
+        span: Range<usize>,
+    },
+    
+    #[error("Invalid duration: {text}")]
+    InvalidDuration {
+        text: String,
+        span: Range<usize>,
+    },
     
     #[error("Invalid shortcut format")]
     InvalidFormat,
 }
 
-impl KeyCombination {
-    /// Convert the combination to a list of key codes
-    pub fn to_keycodes(&self) -> Vec<u16> {
-        let mut codes = Vec::new();
+/// Number of suggestions offered for an unknown key
+const MAX_SUGGESTIONS: usize = 3;
+
+impl ParseError {
+    /// An unknown key error without suggestions
+    pub(crate) fn unknown_key(key: impl Into<String>, span: Range<usize>) -> Self {
+        ParseError::UnknownKey { key: key.into(), span, suggestions: Vec::new() }
+    }
+    
+    /// Byte range of the offending part of the input, if the error has one
+    pub fn span(&self) -> Option<Range<usize>> {
+        match self {
+            ParseError::UnknownKey { span, .. }
+            | ParseError::MultipleMainKeys { span }
+            | ParseError::NoMainKey { span }
+            | ParseError::NotAModifier { span, .. }
+            | ParseError::InvalidDuration { span, .. } => Some(span.clone()),
+            ParseError::EmptyShortcut | ParseError::InvalidFormat => None,
+        }
+    }
+    
+    /// Likely corrections for an unknown key
+    pub fn suggestions(&self) -> &[String] {
+        match self {
+            ParseError::UnknownKey { suggestions, .. } => suggestions,
+            _ => &[],
+        }
+    }
+    
+    /// Fill in corrections for an unknown key from the fuzzy searcher
+    pub(crate) fn with_suggestions(mut self) -> Self {
+        if let ParseError::UnknownKey { key, suggestions, .. } = &mut self {
+            if suggestions.is_empty() {
+                *suggestions = FuzzySearcher::new()
+                    .suggest(key, MAX_SUGGESTIONS)
+                    .into_iter()
+                    .map(|keycode| keycode.name.to_string())
+                    .collect();
+            }
+        }
+        self
+    }
+    
+    /// Move the span by `offset` bytes, for errors in part of a larger input
+    pub(crate) fn offset(self, offset: usize) -> Self {
+        let shift = |span: Range<usize>| span.start + offset..span.end + offset;
+        self.map_span(shift)
+    }
+    
+    /// Replace the span, for errors whose span points into translated text
+    pub(crate) fn with_span(self, span: Range<usize>) -> Self {
+        self.map_span(|_| span.clone())
+    }
+    
+    fn map_span(self, f: impl Fn(Range<usize>) -> Range<usize>) -> Self {
+        match self {
+            ParseError::UnknownKey { key, span, suggestions } => {
+                ParseError::UnknownKey { key, span: f(span), suggestions }
+            }
+            ParseError::MultipleMainKeys { span } => ParseError::MultipleMainKeys { span: f(span) },
+            ParseError::NoMainKey { span } => ParseError::NoMainKey { span: f(span) },
+            ParseError::NotAModifier { key, span } => ParseError::NotAModifier { key, span: f(span) },
+            ParseError::InvalidDuration { text, span } => ParseError::InvalidDuration { text, span: f(span) },
+            other => other,
+        }
+    }
+    
+    /// Caret-style diagnostic pointing at the offending part of `input`
+    ///
+    /// ```text
+    /// Unknown key: Comand
+    ///   Cmd+Comand+A
+    ///       ^^^^^^
+    ///   did you mean: Command?
+    /// ```
+    pub fn diagnostic(&self, input: &str) -> String {
+        let mut lines = vec![self.to_string()];
         
-        // Add modifier codes first
-        for modifier in &self.modifiers {
-            codes.push(modifier.code);
+        if let Some(span) = self.span() {
+            if let (Some(before), Some(marked)) = (input.get(..span.start), input.get(span.clone())) {
+                lines.push(format!("  {}", input));
+                lines.push(format!(
+                    "  {}{}",
+                    " ".repeat(before.chars().count()),
+                    "^".repeat(marked.chars().count().max(1))
+                ));
+            }
         }
         
-        // Add main key code
-        codes.push(self.key.code);
+        if !self.suggestions().is_empty() {
+            lines.push(format!("  did you mean: {}?", self.suggestions().join(", ")));
+        }
         
+        lines.join("\n")
+    }
+}
+
+impl KeyCombination {
+    /// Convert the combination to a list of key codes
+    pub fn to_keycodes(&self) -> Vec<u16> {
+        let mut codes = self.modifiers.codes();
+        codes.push(self.key.code);
         codes
     }
     
-    /// Get a human-readable representation
-    pub fn to_string(&self) -> String {
-        let mut parts = Vec::new();
-        
-        // Add modifiers in standard order
-        let modifier_order = ["Command", "Control", "Option", "Shift"];
-        for name in &modifier_order {
-            if self.modifiers.iter().any(|m| m.name == *name) {
-                parts.push(name.to_string());
-            }
+    /// Explain how a shifted symbol or the Fn layer was expanded, if one was used
+    pub fn expansion(&self) -> Option<String> {
+        let mut notes = Vec::new();
+        if let Some(symbol) = &self.shifted_symbol {
+            notes.push(format!("'{}' is typed as Shift+{}, so Shift was added", symbol, self.key.name));
+        }
+        if let Some(fn_key) = &self.fn_key {
+            notes.push(format!("Fn+{} is {} on Mac keyboards", fn_key.name, self.key.name));
         }
         
-        // Add main key
-        parts.push(self.key.name.clone());
-        
+        if notes.is_empty() {
+            None
+        } else {
+            Some(notes.join("; "))
+        }
+    }
+    
+    /// The same shortcut typed through the Fn layer, e.g. "Command+Fn+LeftArrow" for Command+Home
+    ///
+    /// `None` when the key is not on the Fn layer.
+    pub fn fn_layer_form(&self) -> Option<String> {
+        let source = fn_layer_source(&self.key)?;
+        let mut names = (self.modifiers | Modifiers::FN).names();
+        names.push(source.name);
+        Some(names.join("+"))
+    }
+    
+    /// Get a human-readable representation
+    pub fn to_string(&self) -> String {
+        let mut parts = self.modifiers.names();
+        parts.push(self.key.name);
         parts.join("+")
     }
+    
+    /// The keys pressed, without how the shortcut was written
+    ///
+    /// Left-side modifiers become the plain modifier, the main key becomes the
+    /// canonical key for its code, and the shifted symbol and Fn-layer
+    /// spelling are dropped. "Shift+Cmd+A", "⌘⇧A" and "ShiftLeft+Command+A"
+    /// share one canonical form.
+    pub fn canonical(&self) -> KeyCombination {
+        KeyCombination {
+            modifiers: self.modifiers.canonical(),
+            key: KEY_DATABASE.lookup_by_code(self.key.code).unwrap_or(&self.key).clone(),
+            shifted_symbol: None,
+            fn_key: None,
+        }
+    }
+    
+    /// What equality, hashing and ordering compare: the main key code, then the modifiers
+    fn identity(&self) -> (u16, u16) {
+        (self.key.code, self.modifiers.canonical().bits())
+    }
 }
 
 /// Parse a keyboard shortcut string into a KeyCombination
@@ -71,115 +259,343 @@ impl KeyCombination {
 /// - "Cmd+Shift+S", "⌘⇧S"
 /// - "Ctrl+Option+Delete"
 /// 
-/// Separators can be '+', '-', or no separator for symbols
+/// Separators can be '+', '-', or no separator for symbols. A separator
+/// character where a key is expected is the key itself ("Cmd+-", "Cmd++"),
+/// and `\` escapes the next character (see `split_shortcut` for the grammar).
+///
+/// Shifted symbols such as `{`, `?` or `!` resolve to their base key and add
+/// Shift, so "Cmd+{" is Command+Shift+LeftBracket.
 pub fn parse_shortcut(shortcut: &str) -> Result<KeyCombination, ParseError> {
+    parse_combination(shortcut).map_err(ParseError::with_suggestions)
+}
+
+/// `parse_shortcut` without looking up suggestions for unknown keys
+pub(crate) fn parse_combination(shortcut: &str) -> Result<KeyCombination, ParseError> {
     if shortcut.is_empty() {
         return Err(ParseError::EmptyShortcut);
     }
     
     // Split by common separators
-    let parts = split_shortcut(shortcut);
+    let tokens = split_shortcut(shortcut);
     
-    if parts.is_empty() {
+    if tokens.is_empty() {
         return Err(ParseError::EmptyShortcut);
     }
     
-    let mut modifiers = Vec::new();
+    let mut modifiers = Modifiers::empty();
     let mut main_key = None;
+    let mut shifted_symbol = None;
     
-    for part in parts {
-        if let Some(keycode) = KEY_DATABASE.lookup(&part) {
-            match keycode.category {
-                KeyCategory::ModifierKeys => {
-                    // Avoid duplicates
-                    if !modifiers.iter().any(|m: &KeyCode| m.code == keycode.code) {
-                        modifiers.push(keycode.clone());
-                    }
-                }
-                _ => {
+    for Token { text, span } in tokens {
+        if let Some(keycode) = KEY_DATABASE.lookup(&text) {
+            match Modifiers::from_key(keycode) {
+                Some(flag) => modifiers |= flag,
+                None => {
                     if main_key.is_some() {
-                        return Err(ParseError::MultipleMainKeys);
+                        return Err(ParseError::MultipleMainKeys { span });
                     }
                     main_key = Some(keycode.clone());
                 }
             }
+        } else if let Some(flag) = Modifiers::from_sided_name(&text) {
+            modifiers |= flag;
+        } else if let Some(keycode) = KEY_DATABASE.lookup_shifted(&text) {
+            if main_key.is_some() {
+                return Err(ParseError::MultipleMainKeys { span });
+            }
+            main_key = Some(keycode.clone());
+            shifted_symbol = Some(text);
         } else {
-            return Err(ParseError::UnknownKey(part));
+            return Err(ParseError::unknown_key(text, span));
+        }
+    }
+    
+    let mut key = main_key.ok_or_else(|| ParseError::NoMainKey {
+        span: dangling_separator(shortcut).unwrap_or_else(|| trimmed_span(shortcut)),
+    })?;
+    
+    // Fn turns some keys into others ("Fn+Delete" is ForwardDelete)
+    let mut fn_key = None;
+    if modifiers.contains(Modifiers::FN) {
+        if let Some(effective) = fn_layer_key(&key) {
+            modifiers.remove(Modifiers::FN);
+            fn_key = Some(std::mem::replace(&mut key, effective.clone()));
         }
     }
     
-    let key = main_key.ok_or(ParseError::NoMainKey)?;
+    // A shifted symbol needs Shift held with its base key
+    if shifted_symbol.is_some() && !modifiers.intersects(Modifiers::ANY_SHIFT) {
+        modifiers |= Modifiers::SHIFT;
+    }
+    
+    Ok(KeyCombination { modifiers: modifiers.normalized(), key, shifted_symbol, fn_key })
+}
+
+/// Parse a shortcut made only of modifiers, such as "Cmd+Shift" or "Right Option"
+pub(crate) fn parse_modifiers(shortcut: &str) -> Result<Modifiers, ParseError> {
+    let tokens = split_shortcut(shortcut);
+    if tokens.is_empty() {
+        return Err(ParseError::EmptyShortcut);
+    }
+    
+    let mut modifiers = Modifiers::empty();
+    for Token { text, span } in tokens {
+        let keycode = KEY_DATABASE.lookup(&text);
+        if let Some(flag) = keycode.and_then(Modifiers::from_key) {
+            modifiers |= flag;
+        } else if let Some(flag) = Modifiers::from_sided_name(&text) {
+            modifiers |= flag;
+        } else if keycode.is_some() || KEY_DATABASE.lookup_shifted(&text).is_some() {
+            return Err(ParseError::NotAModifier { key: text, span });
+        } else {
+            return Err(ParseError::unknown_key(text, span));
+        }
+    }
     
-    Ok(KeyCombination { modifiers, key })
+    Ok(modifiers.normalized())
 }
 
-/// Split a shortcut string into parts
-fn split_shortcut(shortcut: &str) -> Vec<String> {
-    let mut parts = Vec::new();
+/// Byte range of a string without its surrounding whitespace
+pub(crate) fn trimmed_span(text: &str) -> Range<usize> {
+    let start = text.len() - text.trim_start().len();
+    start..text.trim_end().len().max(start)
+}
+
+/// Byte ranges of the whitespace-separated words of a string
+pub(crate) fn word_spans(text: &str) -> Vec<Range<usize>> {
+    let mut spans = Vec::new();
+    let mut start = None;
+    
+    for (index, ch) in text.char_indices() {
+        match (ch.is_whitespace(), start) {
+            (false, None) => start = Some(index),
+            (true, Some(word_start)) => {
+                spans.push(word_start..index);
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(word_start) = start {
+        spans.push(word_start..text.len());
+    }
+    
+    spans
+}
+
+/// Characters that separate the keys of a shortcut
+const SEPARATORS: [char; 2] = ['+', '-'];
+
+/// Modifier symbols that stand on their own without a separator
+const MODIFIER_SYMBOLS: [char; 4] = ['⌘', '⌃', '⌥', '⇧'];
+
+/// Escape character that makes the next character part of a key name
+const ESCAPE: char = '\\';
+
+/// A key name in a shortcut and where it was written
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Token {
+    text: String,
+    span: Range<usize>,
+}
+
+/// Split a shortcut string into key names
+///
+/// Grammar, scanned left to right:
+/// - `+` or `-` after a key is a separator; where a key is expected (at the
+///   start or right after a separator) it is the key itself, so "Cmd++",
+///   "Cmd+-" and "Cmd--" all end in the `+`/`-` key
+/// - a separator at the very end is dropped, so "Cmd+" has no main key;
+///   right after a symbol modifier it is the key itself ("⌘-" is ⌘ and `-`)
+/// - `\` escapes the next character ("Cmd+\+"); a lone trailing `\` is the
+///   Backslash key
+/// - ⌘ ⌃ ⌥ ⇧ are keys on their own and need no separator ("⌘⇧A")
+/// - whitespace around keys and separators is ignored
+fn split_shortcut(shortcut: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
     let mut current = String::new();
-    let mut chars = shortcut.chars().peekable();
+    // Span of the key name being collected, without trailing whitespace
+    let mut span = 0..0;
+    // Whether the next separator character is a key rather than a separator
+    let mut expect_key = true;
+    // Separator right after a symbol modifier, kept in case it turns out to be the key
+    let mut pending_separator = None;
+    // Whether the last thing read was a symbol modifier (⌘ ⌃ ⌥ ⇧)
+    let mut after_symbol = false;
+    let mut chars = shortcut.char_indices().peekable();
     
-    while let Some(ch) = chars.next() {
+    while let Some((index, ch)) = chars.next() {
+        let end = index + ch.len_utf8();
         match ch {
-            '+' | '-' => {
-                // Separator found
-                if !current.is_empty() {
-                    parts.push(current.trim().to_string());
-                    current.clear();
+            ESCAPE => {
+                let (escaped, escaped_end) = chars
+                    .next()
+                    .map_or((ESCAPE, end), |(i, c)| (c, i + c.len_utf8()));
+                if current.is_empty() {
+                    span.start = index;
                 }
+                current.push(escaped);
+                span.end = escaped_end;
+                pending_separator = None;
+                expect_key = false;
             }
-            '⌘' | '⌃' | '⌥' | '⇧' => {
-                // Symbol modifier
-                if !current.is_empty() {
-                    parts.push(current.trim().to_string());
-                    current.clear();
-                }
-                parts.push(ch.to_string());
+            c if SEPARATORS.contains(&c) && expect_key => {
+                tokens.push(Token { text: c.to_string(), span: index..end });
+                pending_separator = None;
+                expect_key = false;
+            }
+            c if SEPARATORS.contains(&c) => {
+                push_token(&mut tokens, &mut current, &span);
+                pending_separator = after_symbol.then(|| Token { text: c.to_string(), span: index..end });
+                expect_key = true;
             }
-            _ => {
-                current.push(ch);
+            c if MODIFIER_SYMBOLS.contains(&c) => {
+                push_token(&mut tokens, &mut current, &span);
+                tokens.push(Token { text: c.to_string(), span: index..end });
+                pending_separator = None;
+                expect_key = false;
+                after_symbol = true;
+                continue;
+            }
+            c if c.is_whitespace() && current.is_empty() => {
+                // Leading whitespace of a key
+                continue;
+            }
+            c => {
+                if current.is_empty() {
+                    span.start = index;
+                }
+                current.push(c);
+                if !c.is_whitespace() {
+                    span.end = end;
+                }
+                pending_separator = None;
+                expect_key = false;
             }
         }
+        after_symbol = false;
     }
     
-    // Don't forget the last part
-    if !current.is_empty() {
-        parts.push(current.trim().to_string());
+    push_token(&mut tokens, &mut current, &span);
+    
+    // "⌘-" names the separator character as the key
+    if let Some(separator) = pending_separator {
+        tokens.push(separator);
     }
     
-    parts
+    tokens
+}
+
+/// Span of a separator left dangling at the end of a shortcut ("Cmd+")
+///
+/// Only meaningful once parsing found no main key: an escaped or doubled
+/// trailing separator ("Cmd+\+", "Cmd++") is the key itself.
+pub(crate) fn dangling_separator(shortcut: &str) -> Option<Range<usize>> {
+    let trimmed = shortcut.trim_end();
+    trimmed
+        .strip_suffix(SEPARATORS)
+        .filter(|rest| !rest.trim().is_empty())
+        .map(|rest| rest.len()..trimmed.len())
+}
+
+/// Finish the key name being collected, if any
+fn push_token(tokens: &mut Vec<Token>, current: &mut String, span: &Range<usize>) {
+    let text = current.trim();
+    if !text.is_empty() {
+        tokens.push(Token { text: text.to_string(), span: span.clone() });
+    }
+    current.clear();
+}
+
+/// Escape separator and escape characters so a key name survives `parse_shortcut`
+pub fn escape_key_name(name: &str) -> String {
+    let mut escaped = String::with_capacity(name.len());
+    for ch in name.chars() {
+        if SEPARATORS.contains(&ch) || ch == ESCAPE {
+            escaped.push(ESCAPE);
+        }
+        escaped.push(ch);
+    }
+    escaped
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::keycode::notation::parse_shortcut_notation;
+    use crate::keycode::{KeyCategory, Notation};
+    use std::collections::HashSet;
     
     #[test]
     fn test_parse_simple_shortcut() {
         let combo = parse_shortcut("Cmd+A").unwrap();
-        assert_eq!(combo.modifiers.len(), 1);
-        assert_eq!(combo.modifiers[0].name, "Command");
+        assert_eq!(combo.modifiers, Modifiers::COMMAND);
         assert_eq!(combo.key.name, "A");
     }
     
     #[test]
     fn test_parse_multiple_modifiers() {
         let combo = parse_shortcut("Cmd+Shift+S").unwrap();
-        assert_eq!(combo.modifiers.len(), 2);
-        assert!(combo.modifiers.iter().any(|m| m.name == "Command"));
-        assert!(combo.modifiers.iter().any(|m| m.name == "Shift"));
+        assert_eq!(combo.modifiers, Modifiers::COMMAND | Modifiers::SHIFT);
         assert_eq!(combo.key.name, "S");
     }
     
     #[test]
     fn test_parse_symbol_shortcut() {
         let combo = parse_shortcut("⌘⇧A").unwrap();
-        assert_eq!(combo.modifiers.len(), 2);
-        assert!(combo.modifiers.iter().any(|m| m.name == "Command"));
-        assert!(combo.modifiers.iter().any(|m| m.name == "Shift"));
+        assert_eq!(combo.modifiers, Modifiers::COMMAND | Modifiers::SHIFT);
         assert_eq!(combo.key.name, "A");
     }
     
+    #[test]
+    fn test_parse_side_specific_modifiers() {
+        let combo = parse_shortcut("ShiftRight+A").unwrap();
+        assert_eq!(combo.modifiers, Modifiers::SHIFT_RIGHT);
+        assert_eq!(combo.to_string(), "ShiftRight+A");
+        assert_eq!(combo.to_keycodes(), vec![60, 0]);
+        
+        let combo = parse_shortcut("Right Command+Left Option+Fn+F1").unwrap();
+        assert_eq!(combo.to_string(), "CommandRight+OptionLeft+Fn+F1");
+        assert_eq!(combo.to_keycodes(), vec![54, 58, 63, 122]);
+        
+        let combo = parse_shortcut("Right Cmd+Left Opt+A").unwrap();
+        assert_eq!(combo.modifiers, Modifiers::COMMAND_RIGHT | Modifiers::OPTION_LEFT);
+        
+        // A side already names the key, so the plain modifier adds nothing
+        let combo = parse_shortcut("Shift+ShiftLeft+A").unwrap();
+        assert_eq!(combo.modifiers, Modifiers::SHIFT_LEFT);
+        assert_eq!(combo.to_string(), "ShiftLeft+A");
+        
+        // A shifted symbol keeps the Shift the shortcut named
+        let combo = parse_shortcut("ShiftRight+?").unwrap();
+        assert_eq!(combo.modifiers, Modifiers::SHIFT_RIGHT);
+        assert_eq!(combo.to_keycodes(), vec![60, 44]);
+    }
+    
+    #[test]
+    fn test_parse_fn_layer() {
+        let combo = parse_shortcut("Fn+Delete").unwrap();
+        assert_eq!(combo.key.name, "ForwardDelete");
+        assert!(combo.modifiers.is_empty());
+        assert_eq!(combo.fn_key.as_ref().map(|k| k.name), Some("Delete"));
+        assert_eq!(combo.to_keycodes(), vec![117]);
+        assert_eq!(combo.expansion().unwrap(), "Fn+Delete is ForwardDelete on Mac keyboards");
+        
+        let combo = parse_shortcut("Cmd+Shift+Fn+Left").unwrap();
+        assert_eq!(combo.to_string(), "Command+Shift+Home");
+        assert_eq!(combo.fn_layer_form().as_deref(), Some("Command+Shift+Fn+LeftArrow"));
+        
+        // The reverse form is available for keys typed directly
+        let combo = parse_shortcut("Cmd+End").unwrap();
+        assert!(combo.fn_key.is_none());
+        assert_eq!(combo.fn_layer_form().as_deref(), Some("Command+Fn+RightArrow"));
+        
+        // Keys outside the Fn layer keep Fn
+        let combo = parse_shortcut("Fn+F1").unwrap();
+        assert_eq!(combo.modifiers, Modifiers::FN);
+        assert!(combo.fn_layer_form().is_none());
+    }
+    
     #[test]
     fn test_parse_with_different_separators() {
         let combo1 = parse_shortcut("Cmd+A").unwrap();
@@ -190,9 +606,113 @@ mod tests {
     #[test]
     fn test_parse_errors() {
         assert!(matches!(parse_shortcut(""), Err(ParseError::EmptyShortcut)));
-        assert!(matches!(parse_shortcut("XYZ"), Err(ParseError::UnknownKey(_))));
-        assert!(matches!(parse_shortcut("Cmd+Shift"), Err(ParseError::NoMainKey)));
-        assert!(matches!(parse_shortcut("A+B"), Err(ParseError::MultipleMainKeys)));
+        assert!(matches!(parse_shortcut("XYZ"), Err(ParseError::UnknownKey { .. })));
+        assert!(matches!(parse_shortcut("Cmd+Shift"), Err(ParseError::NoMainKey { .. })));
+        assert!(matches!(parse_shortcut("A+B"), Err(ParseError::MultipleMainKeys { .. })));
+    }
+    
+    #[test]
+    fn test_parse_shifted_symbol() {
+        let combo = parse_shortcut("Cmd+{").unwrap();
+        assert_eq!(combo.key.name, "LeftBracket");
+        assert_eq!(combo.to_string(), "Command+Shift+LeftBracket");
+        assert_eq!(combo.to_keycodes(), vec![55, 56, 33]);
+        assert_eq!(combo.shifted_symbol.as_deref(), Some("{"));
+        assert!(combo.expansion().unwrap().contains("Shift+LeftBracket"));
+        
+        // Shift is not added twice
+        let combo = parse_shortcut("Cmd+Shift+?").unwrap();
+        assert_eq!(combo.to_keycodes(), vec![55, 56, 44]);
+        
+        // The unshifted character needs no Shift
+        let combo = parse_shortcut("Cmd+[").unwrap();
+        assert_eq!(combo.to_keycodes(), vec![55, 33]);
+        assert!(combo.expansion().is_none());
+    }
+    
+    #[test]
+    fn test_separator_characters_as_main_key() {
+        let minus = parse_shortcut("Cmd+-").unwrap();
+        assert_eq!(minus.to_keycodes(), vec![55, 27]);
+        
+        assert_eq!(parse_shortcut("Cmd--").unwrap().key.name, "Minus");
+        assert_eq!(parse_shortcut("Cmd + -").unwrap().key.name, "Minus");
+        assert_eq!(parse_shortcut("Cmd+Shift+-").unwrap().to_string(), "Command+Shift+Minus");
+        assert_eq!(parse_shortcut("⌘-").unwrap().key.name, "Minus");
+        assert_eq!(parse_shortcut("-").unwrap().key.name, "Minus");
+        
+        // '+' is the shifted symbol of Equal
+        let plus = parse_shortcut("Cmd++").unwrap();
+        assert_eq!(plus.to_string(), "Command+Shift+Equal");
+        assert_eq!(parse_shortcut("Cmd-+").unwrap().to_keycodes(), plus.to_keycodes());
+        assert_eq!(parse_shortcut("Cmd+=").unwrap().to_keycodes(), vec![55, 24]);
+    }
+    
+    #[test]
+    fn test_trailing_separator_and_escaping() {
+        // A trailing separator needs a key after it
+        assert_eq!(parse_shortcut("Cmd+"), Err(ParseError::NoMainKey { span: 3..4 }));
+        assert_eq!(parse_shortcut("Ctrl - "), Err(ParseError::NoMainKey { span: 5..6 }));
+        assert_eq!(parse_shortcut("Cmd+Shift+"), Err(ParseError::NoMainKey { span: 9..10 }));
+        assert!(!crate::keycode::is_gesture("Cmd+"));
+        
+        // Escaped separators and backslashes
+        assert_eq!(parse_shortcut(r"Cmd+\-").unwrap().key.name, "Minus");
+        assert_eq!(parse_shortcut(r"Cmd+\+").unwrap().key.name, "Equal");
+        assert_eq!(parse_shortcut(r"Cmd+\\").unwrap().key.name, "Backslash");
+        assert_eq!(parse_shortcut(r"Cmd+\").unwrap().key.name, "Backslash");
+        
+        let names: Vec<String> = split_shortcut("Cmd + Page Up").into_iter().map(|t| t.text).collect();
+        assert_eq!(names, vec!["Cmd", "Page Up"]);
+        assert!(matches!(parse_shortcut("Cmd++A"), Err(ParseError::MultipleMainKeys { .. })));
+    }
+    
+    #[test]
+    fn test_every_key_can_be_the_main_key() {
+        for key in KEY_DATABASE.all_keys() {
+            if key.category == KeyCategory::ModifierKeys {
+                continue;
+            }
+            
+            for name in std::iter::once(&key.name).chain(key.aliases) {
+                let shortcut = format!("Cmd+{}", escape_key_name(name));
+                let combo = parse_shortcut(&shortcut)
+                    .unwrap_or_else(|e| panic!("{} failed: {}", shortcut, e));
+                assert_eq!(combo.key.name, key.name, "{}", shortcut);
+            }
+            
+            if let Some(symbol) = key.shifted {
+                let combo = parse_shortcut(&format!("Cmd+{}", escape_key_name(symbol))).unwrap();
+                assert_eq!(combo.key.name, key.name);
+            }
+        }
+    }
+    
+    #[test]
+    fn test_token_spans() {
+        let spans: Vec<_> = split_shortcut("⌘ + Page Up+\\-").into_iter().map(|t| t.span).collect();
+        assert_eq!(spans, vec![0..3, 6..13, 14..16]);
+    }
+    
+    #[test]
+    fn test_error_spans_and_suggestions() {
+        let err = parse_shortcut("Cmd+Comand+A").unwrap_err();
+        assert_eq!(err.span(), Some(4..10));
+        assert_eq!(err.suggestions().first().map(String::as_str), Some("Command"));
+        let diagnostic = err.diagnostic("Cmd+Comand+A");
+        assert!(diagnostic.starts_with("Unknown key: Comand\n  Cmd+Comand+A\n      ^^^^^^\n"));
+        assert!(diagnostic.contains("did you mean: Command"));
+        
+        // Repeated letters still find the intended key
+        let err = parse_shortcut("Commmand+S").unwrap_err();
+        assert!(err.suggestions().iter().any(|s| s == "Command"));
+        
+        let err = parse_shortcut("⌘A+B").unwrap_err();
+        assert_eq!(err, ParseError::MultipleMainKeys { span: 5..6 });
+        
+        let err = parse_shortcut(" Cmd+Shift ").unwrap_err();
+        assert_eq!(err.span(), Some(1..10));
+        assert!(err.diagnostic(" Cmd+Shift ").ends_with("   ^^^^^^^^^"));
     }
     
     #[test]
@@ -201,4 +721,38 @@ mod tests {
         // Should normalize order to Command+Shift+A
         assert_eq!(combo.to_string(), "Command+Shift+A");
     }
+    
+    #[test]
+    fn test_equal_however_written() {
+        let canonical = parse_shortcut("Cmd+Shift+A").unwrap();
+        for shortcut in ["Shift+Cmd+A", "⌘⇧A", "ShiftLeft+Command+A", "Left Cmd+⇧+a"] {
+            let combination = parse_shortcut(shortcut).unwrap();
+            assert_eq!(combination, canonical, "{}", shortcut);
+            assert_eq!(combination.canonical().to_string(), "Command+Shift+A");
+        }
+        
+        // A shifted symbol is its base key with Shift
+        assert_eq!(parse_shortcut("Cmd+{").unwrap(), parse_shortcut("Cmd+Shift+[").unwrap());
+        assert!(parse_shortcut("Cmd+{").unwrap().canonical().shifted_symbol.is_none());
+        
+        // Notations and the Fn layer resolve to the same keys
+        let emacs = parse_shortcut_notation("s-<home>", Notation::Emacs).unwrap();
+        assert_eq!(emacs.chords[0], parse_shortcut("Cmd+Fn+Left").unwrap());
+        
+        // The right-hand key is a different key
+        assert_ne!(parse_shortcut("ShiftRight+A").unwrap(), parse_shortcut("Shift+A").unwrap());
+        assert_eq!(parse_shortcut("NumpadClear").unwrap().canonical().key.name, "Clear");
+    }
+    
+    #[test]
+    fn test_hash_and_order() {
+        let shortcuts = ["Cmd+Shift+A", "Shift+Cmd+A", "⌘⇧A", "Cmd+A", "A"];
+        let set: HashSet<KeyCombination> = shortcuts.iter().map(|s| parse_shortcut(s).unwrap()).collect();
+        assert_eq!(set.len(), 3);
+        
+        let mut sorted: Vec<KeyCombination> = set.into_iter().collect();
+        sorted.sort();
+        let names: Vec<String> = sorted.iter().map(KeyCombination::to_string).collect();
+        assert_eq!(names, vec!["A", "Command+A", "Command+Shift+A"]);
+    }
 }
\ No newline at end of file
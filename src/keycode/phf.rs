@@ -0,0 +1,174 @@
+//! Minimal perfect hashing over case-insensitive strings
+//!
+//! Uses the hash-and-displace scheme: keys are split into small buckets, and
+//! each bucket gets a displacement pair that moves all of its keys into free
+//! slots of a table with exactly one slot per key. Also compiled into
+//! `build.rs`, so it only depends on `std`.
+
+/// Average number of keys per bucket
+const BUCKET_SIZE: usize = 4;
+
+/// Hash values derived from one key
+#[derive(Debug, Clone, Copy)]
+pub struct Hashes {
+    bucket: u32,
+    f1: u32,
+    f2: u32,
+}
+
+/// A perfect hash index over a fixed set of strings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Index {
+    /// Seed that made every bucket placeable
+    pub seed: u64,
+    /// Displacement pair for each bucket
+    pub displacements: Vec<(u32, u32)>,
+    /// Position of the input string stored in each slot
+    pub slots: Vec<usize>,
+}
+
+/// Hash a string as if it were lowercased, without allocating
+pub fn hash(text: &str, seed: u64) -> Hashes {
+    // FNV-1a over the UTF-8 bytes of the lowercased characters
+    let mut h = 0xcbf2_9ce4_8422_2325 ^ mix(seed);
+    for ch in text.chars().flat_map(char::to_lowercase) {
+        let mut buf = [0; 4];
+        for byte in ch.encode_utf8(&mut buf).bytes() {
+            h ^= u64::from(byte);
+            h = h.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+    
+    let a = mix(h);
+    let b = mix(a);
+    Hashes {
+        bucket: (a >> 32) as u32,
+        f1: a as u32,
+        f2: b as u32,
+    }
+}
+
+/// Slot a key lands in, given the displacement table and slot count
+pub fn slot(hashes: Hashes, displacements: &[(u32, u32)], len: usize) -> Option<usize> {
+    if displacements.is_empty() || len == 0 {
+        return None;
+    }
+    
+    let (d1, d2) = displacements[hashes.bucket as usize % displacements.len()];
+    Some(displace(hashes, d1, d2) % len)
+}
+
+/// Compare an already lowercased string with a query, ignoring the query's case
+pub fn eq_ignore_case(lowercase: &str, query: &str) -> bool {
+    query.chars().flat_map(char::to_lowercase).eq(lowercase.chars())
+}
+
+/// Build a perfect hash index over distinct, lowercased strings
+///
+/// Seeds are tried in order from zero, so the same input always produces the
+/// same index.
+pub fn build(keys: &[&str]) -> Index {
+    (0..)
+        .find_map(|seed| try_build(keys, seed))
+        .expect("no seed produces a perfect hash")
+}
+
+fn try_build(keys: &[&str], seed: u64) -> Option<Index> {
+    let hashes: Vec<Hashes> = keys.iter().map(|key| hash(key, seed)).collect();
+    let bucket_count = keys.len().div_ceil(BUCKET_SIZE).max(1);
+    
+    let mut buckets = vec![Vec::new(); bucket_count];
+    for (index, h) in hashes.iter().enumerate() {
+        buckets[h.bucket as usize % bucket_count].push(index);
+    }
+    
+    // Place the largest buckets first while the table is still empty
+    let mut order: Vec<usize> = (0..bucket_count).collect();
+    order.sort_by_key(|&bucket| std::cmp::Reverse(buckets[bucket].len()));
+    
+    let len = keys.len();
+    let mut slots: Vec<Option<usize>> = vec![None; len];
+    let mut displacements = vec![(0, 0); bucket_count];
+    
+    'buckets: for bucket in order {
+        if buckets[bucket].is_empty() {
+            continue;
+        }
+        
+        for d1 in 0..len as u32 {
+            for d2 in 0..len as u32 {
+                let mut placed: Vec<usize> = Vec::new();
+                let fits = buckets[bucket].iter().all(|&key| {
+                    let target = displace(hashes[key], d1, d2) % len;
+                    let free = slots[target].is_none() && !placed.contains(&target);
+                    placed.push(target);
+                    free
+                });
+                
+                if fits {
+                    for (&key, &target) in buckets[bucket].iter().zip(&placed) {
+                        slots[target] = Some(key);
+                    }
+                    displacements[bucket] = (d1, d2);
+                    continue 'buckets;
+                }
+            }
+        }
+        
+        return None;
+    }
+    
+    Some(Index {
+        seed,
+        displacements,
+        slots: slots.into_iter().map(|slot| slot.expect("every key is placed")).collect(),
+    })
+}
+
+fn displace(hashes: Hashes, d1: u32, d2: u32) -> usize {
+    hashes.f1
+        .wrapping_add(d1.wrapping_mul(hashes.f2))
+        .wrapping_add(d2) as usize
+}
+
+/// SplitMix64 finalizer
+fn mix(mut z: u64) -> u64 {
+    z = z.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_every_key_has_its_own_slot() {
+        let keys = ["command", "cmd", "⌘", "a", "space", "f17", "{"];
+        let index = build(&keys);
+        
+        assert_eq!(index.slots.len(), keys.len());
+        for (position, key) in keys.iter().enumerate() {
+            let found = slot(hash(key, index.seed), &index.displacements, keys.len()).unwrap();
+            assert_eq!(index.slots[found], position);
+        }
+    }
+    
+    #[test]
+    fn test_hash_ignores_case() {
+        let index = build(&["space"]);
+        let lower = slot(hash("space", index.seed), &index.displacements, 1);
+        let upper = slot(hash("SPACE", index.seed), &index.displacements, 1);
+        assert_eq!(lower, upper);
+        
+        assert!(eq_ignore_case("space", "SpAcE"));
+        assert!(!eq_ignore_case("space", "spaces"));
+    }
+    
+    #[test]
+    fn test_build_is_deterministic() {
+        let keys = ["return", "enter", "↩", "tab", "⇥"];
+        assert_eq!(build(&keys), build(&keys));
+    }
+}
@@ -0,0 +1,216 @@
+use crate::keycode::output::PostedEvent;
+use crate::keycode::{KeyCategory, KeyCode, KeyCombination, KeySequence, Modifiers};
+
+/// Quartz `CGEventFlags` constant set while each modifier is down
+const EVENT_FLAGS: [(Modifiers, &str); 6] = [
+    (Modifiers::ANY_COMMAND, "Quartz.kCGEventFlagMaskCommand"),
+    (Modifiers::ANY_SHIFT, "Quartz.kCGEventFlagMaskShift"),
+    (Modifiers::ANY_OPTION, "Quartz.kCGEventFlagMaskAlternate"),
+    (Modifiers::ANY_CONTROL, "Quartz.kCGEventFlagMaskControl"),
+    (Modifiers::FN, "Quartz.kCGEventFlagMaskSecondaryFn"),
+    (Modifiers::CAPS_LOCK, "Quartz.kCGEventFlagMaskAlphaShift"),
+];
+
+/// Helper the generated statements call to post one key event
+const QUARTZ_PRELUDE: &str = "\
+import time
+
+import Quartz
+
+
+def post_key(key, down, flags):
+    event = Quartz.CGEventCreateKeyboardEvent(None, key, down)
+    Quartz.CGEventSetFlags(event, flags)
+    Quartz.CGEventPost(Quartz.kCGHIDEventTap, event)
+";
+
+/// pyautogui's macOS key names, as (key name, pyautogui name)
+///
+/// Letters, digits and F-keys are their lowercase name and are not listed;
+/// punctuation is the character itself. Keys missing here (Section,
+/// CommandRight, the keypad, ContextMenu...) have no pyautogui name on
+/// macOS and need the Quartz form.
+const PYAUTOGUI_NAMES: &[(&str, &str)] = &[
+    ("Command", "command"),
+    ("CommandLeft", "command"),
+    ("Shift", "shift"),
+    ("ShiftLeft", "shift"),
+    ("ShiftRight", "shiftright"),
+    ("Option", "option"),
+    ("OptionLeft", "option"),
+    ("OptionRight", "optionright"),
+    ("Control", "ctrl"),
+    ("ControlLeft", "ctrl"),
+    ("ControlRight", "ctrlright"),
+    ("CapsLock", "capslock"),
+    ("Fn", "fn"),
+    ("LeftArrow", "left"),
+    ("RightArrow", "right"),
+    ("UpArrow", "up"),
+    ("DownArrow", "down"),
+    ("PageUp", "pageup"),
+    ("PageDown", "pagedown"),
+    ("Home", "home"),
+    ("End", "end"),
+    ("Space", "space"),
+    ("Return", "return"),
+    ("Tab", "tab"),
+    ("Delete", "backspace"),
+    ("ForwardDelete", "delete"),
+    ("Escape", "esc"),
+    ("Help", "help"),
+    ("Eisu", "eisu"),
+    ("Kana", "kana"),
+    ("Mute", "volumemute"),
+    ("VolumeUp", "volumeup"),
+    ("VolumeDown", "volumedown"),
+    ("Grave", "`"),
+    ("Minus", "-"),
+    ("Equal", "="),
+    ("LeftBracket", "["),
+    ("RightBracket", "]"),
+    ("Backslash", "\\"),
+    ("Semicolon", ";"),
+    ("Quote", "'"),
+    ("Comma", ","),
+    ("Period", "."),
+    ("Slash", "/"),
+    ("JisYen", "yen"),
+];
+
+/// pyautogui's name for a key, e.g. "command", "a", "f5" or "["
+pub fn pyautogui_name(key: &KeyCode) -> Option<String> {
+    match key.category {
+        KeyCategory::Letters | KeyCategory::Numbers | KeyCategory::FunctionKeys => Some(key.name.to_lowercase()),
+        _ => PYAUTOGUI_NAMES
+            .iter()
+            .find(|(name, _)| *name == key.name)
+            .map(|(_, pyautogui)| pyautogui.to_string()),
+    }
+}
+
+/// A Python string literal
+fn quote(text: &str) -> String {
+    format!("'{}'", text.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+/// The `post_key` call for one event
+fn post_key(event: &PostedEvent) -> String {
+    let masks: Vec<&str> = event.flags
+        .iter()
+        .filter_map(|group| EVENT_FLAGS.iter().find(|(flag, _)| flag == group).map(|(_, mask)| *mask))
+        .collect();
+    let flags = if masks.is_empty() { "0".to_string() } else { masks.join(" | ") };
+    let down = if event.down { "True" } else { "False" };
+    format!("post_key({}, {}, {})", event.code, down, flags)
+}
+
+impl KeyCombination {
+    /// The pyautogui call pressing the combination, None when a key has no pyautogui name
+    ///
+    /// `pyautogui.hotkey('command', 'shift', 'a')`, or `pyautogui.press('esc')` without modifiers.
+    pub fn pyautogui_call(&self) -> Option<String> {
+        let mut names = Vec::new();
+        for key in self.modifiers.keys().into_iter().chain(std::iter::once(&self.key)) {
+            names.push(quote(&pyautogui_name(key)?));
+        }
+        
+        if names.len() == 1 {
+            Some(format!("pyautogui.press({})", names[0]))
+        } else {
+            Some(format!("pyautogui.hotkey({})", names.join(", ")))
+        }
+    }
+}
+
+impl KeySequence {
+    /// Python program posting each chord's key events in turn
+    ///
+    /// The sequence's `delay` becomes a `time.sleep` between chords.
+    pub fn to_python(&self) -> String {
+        let mut lines = vec![QUARTZ_PRELUDE.to_string()];
+        for (i, chord) in self.chords.iter().enumerate() {
+            lines.push(String::new());
+            if let (Some(seconds), true) = (self.delay, i > 0) {
+                lines.push(format!("time.sleep({})", seconds));
+            }
+            lines.push(format!("# {}", chord.to_string()));
+            lines.extend(chord.posted_events().iter().map(post_key));
+        }
+        lines.join("\n")
+    }
+    
+    /// Python program pressing each chord with pyautogui, None when a key has no pyautogui name
+    pub fn to_pyautogui(&self) -> Option<String> {
+        let imports = if self.delay.is_some() && !self.is_single() {
+            "import time\n\nimport pyautogui\n"
+        } else {
+            "import pyautogui\n"
+        };
+        
+        let mut lines = vec![imports.to_string()];
+        for (i, chord) in self.chords.iter().enumerate() {
+            if let (Some(seconds), true) = (self.delay, i > 0) {
+                lines.push(format!("time.sleep({})", seconds));
+            }
+            lines.push(chord.pyautogui_call()?);
+        }
+        Some(lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keycode::{parse_shortcut, parse_shortcut_sequence, KEY_DATABASE};
+    
+    #[test]
+    fn test_pyautogui_names() {
+        let name = |key: &str| pyautogui_name(KEY_DATABASE.lookup(key).unwrap());
+        assert_eq!(name("A").as_deref(), Some("a"));
+        assert_eq!(name("7").as_deref(), Some("7"));
+        assert_eq!(name("F12").as_deref(), Some("f12"));
+        assert_eq!(name("Escape").as_deref(), Some("esc"));
+        assert_eq!(name("ForwardDelete").as_deref(), Some("delete"));
+        assert_eq!(name("Backslash").as_deref(), Some("\\"));
+        assert!(name("Section").is_none());
+        
+        for (key, _) in PYAUTOGUI_NAMES {
+            assert!(KEY_DATABASE.lookup(key).is_some(), "{}", key);
+        }
+    }
+    
+    #[test]
+    fn test_pyautogui_call() {
+        let call = |shortcut: &str| parse_shortcut(shortcut).unwrap().pyautogui_call();
+        assert_eq!(call("Cmd+Shift+A").as_deref(), Some("pyautogui.hotkey('command', 'shift', 'a')"));
+        assert_eq!(call("Escape").as_deref(), Some("pyautogui.press('esc')"));
+        assert_eq!(call("Cmd+\\").as_deref(), Some("pyautogui.hotkey('command', '\\\\')"));
+        assert!(call("Cmd+Section").is_none());
+        assert_eq!(call("Fn+Delete").as_deref(), Some("pyautogui.press('delete')"));
+        
+        let sequence = KeySequence { delay: Some(0.2), ..parse_shortcut_sequence("Ctrl+X Ctrl+S").unwrap() };
+        assert_eq!(
+            sequence.to_pyautogui().unwrap(),
+            "import time\n\nimport pyautogui\n\n\
+             pyautogui.hotkey('ctrl', 'x')\n\
+             time.sleep(0.2)\n\
+             pyautogui.hotkey('ctrl', 's')"
+        );
+    }
+    
+    #[test]
+    fn test_combination_python() {
+        let python = KeySequence::from(parse_shortcut("Cmd+Shift+A").unwrap()).to_python();
+        assert!(python.starts_with(QUARTZ_PRELUDE));
+        assert!(python.ends_with(
+            "# Command+Shift+A\n\
+             post_key(55, True, Quartz.kCGEventFlagMaskCommand)\n\
+             post_key(56, True, Quartz.kCGEventFlagMaskCommand | Quartz.kCGEventFlagMaskShift)\n\
+             post_key(0, True, Quartz.kCGEventFlagMaskCommand | Quartz.kCGEventFlagMaskShift)\n\
+             post_key(0, False, Quartz.kCGEventFlagMaskCommand | Quartz.kCGEventFlagMaskShift)\n\
+             post_key(56, False, Quartz.kCGEventFlagMaskCommand)\n\
+             post_key(55, False, 0)"
+        ));
+    }
+}
@@ -0,0 +1,311 @@
+//! Key data file format and validation
+//!
+//! Also compiled into `build.rs`, which validates `data/keycodes.json` with the
+//! same rules before generating the static key tables, so this module may only
+//! depend on `std`, `serde` and the sibling `types` module.
+
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use super::types::{CodeSource, Confidence, HidUsage, KeyCategory, Layout, VerificationMethod};
+
+/// Version of the key data file format understood by this build
+pub const KEY_DATA_VERSION: u32 = 1;
+
+/// Highest key code accepted from a data file
+pub const MAX_KEY_CODE: u16 = 255;
+
+/// On-disk layout of a key data file
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct KeyDataFile {
+    pub version: u32,
+    pub keys: Vec<KeyRecord>,
+}
+
+/// A key as written in the data file
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct KeyRecord {
+    pub name: String,
+    pub code: u16,
+    pub category: KeyCategory,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    #[serde(default)]
+    pub shifted: Option<String>,
+    #[serde(default = "default_layouts")]
+    pub layouts: Vec<Layout>,
+    #[serde(default)]
+    pub carbon_constant: Option<String>,
+    #[serde(default)]
+    pub glyph: Option<String>,
+    #[serde(default)]
+    pub hid_usage: Option<HidUsage>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub printable: bool,
+    #[serde(default)]
+    pub provenance: Option<ProvenanceRecord>,
+}
+
+/// Provenance of a key as written in the data file
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ProvenanceRecord {
+    pub source: CodeSource,
+    #[serde(default)]
+    pub verified_by: Option<VerificationMethod>,
+    #[serde(default)]
+    pub macos_version: Option<String>,
+    #[serde(default)]
+    pub keyboard: Option<String>,
+    #[serde(default)]
+    pub last_verified: Option<String>,
+    pub confidence: Confidence,
+}
+
+/// How a lookup string refers to a key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryKind {
+    /// The key's own name
+    Name,
+    /// One of the key's aliases
+    Alias,
+    /// The symbol the key types with Shift held
+    Shifted,
+}
+
+fn default_layouts() -> Vec<Layout> {
+    Layout::all().to_vec()
+}
+
+/// Parse and validate the contents of a key data file
+pub fn parse_key_data(data: &str) -> Result<Vec<KeyRecord>, String> {
+    let file: KeyDataFile = serde_json::from_str(data)
+        .map_err(|e| format!("invalid key data: {}", e))?;
+    
+    if file.version != KEY_DATA_VERSION {
+        return Err(format!(
+            "unsupported key data version {} (expected {})",
+            file.version, KEY_DATA_VERSION
+        ));
+    }
+    
+    validate(&file.keys)?;
+    Ok(file.keys)
+}
+
+/// Check names, codes and lookup strings of a list of keys
+///
+/// Names, aliases and shifted symbols share one case-insensitive namespace, so
+/// every lookup string resolves to exactly one key.
+pub fn validate(keys: &[KeyRecord]) -> Result<(), String> {
+    if keys.is_empty() {
+        return Err("key data contains no keys".to_string());
+    }
+    
+    let mut names = HashMap::new();
+    for (index, key) in keys.iter().enumerate() {
+        if key.name.trim().is_empty() {
+            return Err(format!("key #{} has an empty name", index));
+        }
+        if key.code > MAX_KEY_CODE {
+            return Err(format!(
+                "key '{}' has out-of-range code {} (max {})",
+                key.name, key.code, MAX_KEY_CODE
+            ));
+        }
+        if let Some(constant) = &key.carbon_constant {
+            if !constant.starts_with("kVK_") {
+                return Err(format!(
+                    "key '{}' has invalid Carbon constant '{}' (expected kVK_*)",
+                    key.name, constant
+                ));
+            }
+        }
+        if key.layouts.is_empty() {
+            return Err(format!("key '{}' is not available on any layout", key.name));
+        }
+        if let Some(provenance) = &key.provenance {
+            validate_provenance(&key.name, provenance)?;
+        }
+        if names.insert(key.name.to_lowercase(), &key.name).is_some() {
+            return Err(format!("duplicate key name '{}'", key.name));
+        }
+    }
+    
+    let mut aliases = HashMap::new();
+    let mut shifted = HashMap::new();
+    for key in keys {
+        for alias in &key.aliases {
+            let alias_lower = alias.to_lowercase();
+            if alias.trim().is_empty() {
+                return Err(format!("key '{}' has an empty alias", key.name));
+            }
+            if names.contains_key(&alias_lower) {
+                return Err(format!(
+                    "alias '{}' of key '{}' clashes with a key name",
+                    alias, key.name
+                ));
+            }
+            if let Some(owner) = aliases.insert(alias_lower, &key.name) {
+                return Err(format!(
+                    "alias '{}' is used by both '{}' and '{}'",
+                    alias, owner, key.name
+                ));
+            }
+        }
+        
+        if let Some(symbol) = &key.shifted {
+            if symbol.trim().is_empty() {
+                return Err(format!("key '{}' has an empty shifted symbol", key.name));
+            }
+            if names.contains_key(&symbol.to_lowercase()) {
+                return Err(format!(
+                    "shifted symbol '{}' of key '{}' clashes with a key name",
+                    symbol, key.name
+                ));
+            }
+            if let Some(owner) = shifted.insert(symbol.to_lowercase(), &key.name) {
+                return Err(format!(
+                    "shifted symbol '{}' is used by both '{}' and '{}'",
+                    symbol, owner, key.name
+                ));
+            }
+        }
+    }
+    
+    // A shifted symbol that is also an alias would make lookups ambiguous
+    for key in keys {
+        if let Some(symbol) = &key.shifted {
+            if aliases.contains_key(&symbol.to_lowercase()) {
+                return Err(format!(
+                    "shifted symbol '{}' of key '{}' clashes with an alias",
+                    symbol, key.name
+                ));
+            }
+        }
+    }
+    
+    Ok(())
+}
+
+/// Check that verification details only appear together with a verification
+fn validate_provenance(name: &str, provenance: &ProvenanceRecord) -> Result<(), String> {
+    let verified = provenance.verified_by.is_some();
+    
+    if verified != provenance.last_verified.is_some() {
+        return Err(format!(
+            "key '{}' must give both verified_by and last_verified, or neither", name
+        ));
+    }
+    if !verified && (provenance.macos_version.is_some() || provenance.keyboard.is_some()) {
+        return Err(format!(
+            "key '{}' names a macOS version or keyboard without a verification", name
+        ));
+    }
+    if let Some(date) = &provenance.last_verified {
+        if !is_iso_date(date) {
+            return Err(format!(
+                "key '{}' has invalid last_verified date '{}' (expected YYYY-MM-DD)",
+                name, date
+            ));
+        }
+    }
+    
+    Ok(())
+}
+
+/// Check for a YYYY-MM-DD date
+fn is_iso_date(date: &str) -> bool {
+    let parts: Vec<&str> = date.split('-').collect();
+    let valid = |part: &str, len: usize, max: u32| {
+        part.len() == len
+            && part.chars().all(|c| c.is_ascii_digit())
+            && part.parse::<u32>().is_ok_and(|n| (1..=max).contains(&n))
+    };
+    
+    parts.len() == 3 && valid(parts[0], 4, 9999) && valid(parts[1], 2, 12) && valid(parts[2], 2, 31)
+}
+
+/// Every lookup string of a validated key list, lowercased, with the index of its key
+pub fn lookup_entries(keys: &[KeyRecord]) -> Vec<(String, usize, EntryKind)> {
+    let mut entries = Vec::new();
+    
+    for (index, key) in keys.iter().enumerate() {
+        entries.push((key.name.to_lowercase(), index, EntryKind::Name));
+        for alias in &key.aliases {
+            entries.push((alias.to_lowercase(), index, EntryKind::Alias));
+        }
+        if let Some(symbol) = &key.shifted {
+            entries.push((symbol.to_lowercase(), index, EntryKind::Shifted));
+        }
+    }
+    
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_record_from_json() {
+        let key: KeyRecord = serde_json::from_str(
+            r#"{"name": "Delete", "code": 51, "category": "special_keys",
+                "carbon_constant": "kVK_Delete", "glyph": "⌫",
+                "hid_usage": {"page": 7, "id": 42}}"#
+        ).unwrap();
+        
+        assert_eq!(key.carbon_constant.as_deref(), Some("kVK_Delete"));
+        assert_eq!(key.glyph.as_deref(), Some("⌫"));
+        assert_eq!(key.hid_usage.unwrap().to_string(), "0x07:0x2A");
+        assert!(!key.printable);
+        assert_eq!(key.layouts, Layout::all());
+    }
+    
+    #[test]
+    fn test_provenance_is_validated() {
+        let parse = |provenance: &str| {
+            let data = format!(
+                r#"{{"version": 1, "keys": [{{"name": "A", "code": 0, "category": "letters", "provenance": {}}}]}}"#,
+                provenance
+            );
+            parse_key_data(&data)
+        };
+        
+        assert!(parse(r#"{"source": "apple_header", "confidence": "medium"}"#).is_ok());
+        assert!(parse(
+            r#"{"source": "apple_header", "verified_by": "manual_test", "macos_version": "14.5",
+                "keyboard": "Magic Keyboard (A2450)", "last_verified": "2025-07-18", "confidence": "high"}"#
+        ).is_ok());
+        
+        // Verification date without a method
+        assert!(parse(r#"{"source": "observed", "last_verified": "2025-07-18", "confidence": "low"}"#).is_err());
+        // Keyboard without a verification
+        assert!(parse(r#"{"source": "observed", "keyboard": "MacBook Pro", "confidence": "low"}"#).is_err());
+        // Malformed date
+        let err = parse(
+            r#"{"source": "observed", "verified_by": "automated_test", "last_verified": "18/07/2025", "confidence": "low"}"#
+        ).unwrap_err();
+        assert!(err.contains("expected YYYY-MM-DD"));
+        // Unknown confidence
+        assert!(parse(r#"{"source": "observed", "confidence": "certain"}"#).is_err());
+    }
+    
+    #[test]
+    fn test_lookup_entries() {
+        let keys: Vec<KeyRecord> = serde_json::from_str(
+            r#"[{"name": "LeftBracket", "code": 33, "category": "punctuation",
+                 "aliases": ["["], "shifted": "{"}]"#
+        ).unwrap();
+        
+        assert_eq!(lookup_entries(&keys), vec![
+            ("leftbracket".to_string(), 0, EntryKind::Name),
+            ("[".to_string(), 0, EntryKind::Alias),
+            ("{".to_string(), 0, EntryKind::Shifted),
+        ]);
+    }
+}
@@ -9,73 +9,85 @@ pub static F_KEY_SECONDARY_FUNCTIONS: Lazy<HashMap<&'static str, SecondaryFuncti
     // F1-F12 secondary functions on modern Mac keyboards
     map.insert("F1", SecondaryFunction {
         name: "Brightness Down",
-        keycode: 107,  // F14
+        keycode: Some(107),  // F14
+        media_key: Some("BrightnessDown"),
         description: "Decrease display brightness",
     });
     
     map.insert("F2", SecondaryFunction {
         name: "Brightness Up",
-        keycode: 113,  // F15
+        keycode: Some(113),  // F15
+        media_key: Some("BrightnessUp"),
         description: "Increase display brightness",
     });
     
     map.insert("F3", SecondaryFunction {
         name: "Mission Control",
-        keycode: 160,  // F17
+        keycode: Some(160),  // F17
+        media_key: None,
         description: "Show all open windows",
     });
     
     map.insert("F4", SecondaryFunction {
         name: "Launchpad",
-        keycode: 131,  // F18
+        keycode: Some(131),  // F18
+        media_key: Some("Launchpad"),
         description: "Show all apps",
     });
     
     map.insert("F5", SecondaryFunction {
         name: "Keyboard Illumination Down",
-        keycode: 105,  // F13
+        keycode: Some(105),  // F13
+        media_key: Some("IlluminationDown"),
         description: "Decrease keyboard backlight",
     });
     
     map.insert("F6", SecondaryFunction {
         name: "Keyboard Illumination Up",
-        keycode: 106,  // F16
+        keycode: Some(106),  // F16
+        media_key: Some("IlluminationUp"),
         description: "Increase keyboard backlight",
     });
     
     map.insert("F7", SecondaryFunction {
         name: "Previous Track",
-        keycode: 98,  // F7 actually sends its own code for media keys
+        keycode: None,  // Media key, no virtual key code
+        media_key: Some("PreviousTrack"),
         description: "Skip to previous track",
     });
     
     map.insert("F8", SecondaryFunction {
         name: "Play/Pause",
-        keycode: 100,  // F8
+        keycode: None,  // Media key, no virtual key code
+        media_key: Some("PlayPause"),
         description: "Play or pause media",
     });
     
     map.insert("F9", SecondaryFunction {
         name: "Next Track",
-        keycode: 101,  // F9
+        keycode: None,  // Media key, no virtual key code
+        media_key: Some("NextTrack"),
         description: "Skip to next track",
     });
     
     map.insert("F10", SecondaryFunction {
         name: "Mute",
-        keycode: 74,  // Mute key
+        keycode: Some(74),  // Mute key
+        media_key: Some("Mute"),
         description: "Mute/unmute audio",
     });
     
     map.insert("F11", SecondaryFunction {
         name: "Volume Down",
-        keycode: 73,  // Volume Down
+        keycode: Some(73),  // Volume Down
+        media_key: Some("VolumeDown"),
         description: "Decrease volume",
     });
     
     map.insert("F12", SecondaryFunction {
         name: "Volume Up",
-        keycode: 72,  // Volume Up
+        keycode: Some(72),  // Volume Up
+        media_key: Some("VolumeUp"),
         description: "Increase volume",
     });
     
@@ -85,7 +97,10 @@ pub static F_KEY_SECONDARY_FUNCTIONS: Lazy<HashMap<&'static str, SecondaryFuncti
 #[derive(Debug, Clone)]
 pub struct SecondaryFunction {
     pub name: &'static str,
-    pub keycode: u8,
+    /// Key code that triggers the function, if it has one
+    pub keycode: Option<u8>,
+    /// Name of the matching entry in `MEDIA_KEYS`, for system-defined media keys
+    pub media_key: Option<&'static str>,
     pub description: &'static str,
 }
 
@@ -148,6 +163,22 @@ mod tests {
     fn test_get_secondary_function() {
         let f3_secondary = get_secondary_function("F3").unwrap();
         assert_eq!(f3_secondary.name, "Mission Control");
-        assert_eq!(f3_secondary.keycode, 160);
+        assert_eq!(f3_secondary.keycode, Some(160));
+    }
+    
+    #[test]
+    fn test_media_secondary_functions() {
+        let f8_secondary = get_secondary_function("F8").unwrap();
+        assert_eq!(f8_secondary.keycode, None);
+        
+        let media = crate::keycode::lookup_media_key(f8_secondary.media_key.unwrap()).unwrap();
+        assert_eq!(media.nx_keytype, 16);
+        
+        // Every linked media key must exist
+        for secondary in F_KEY_SECONDARY_FUNCTIONS.values() {
+            if let Some(name) = secondary.media_key {
+                assert!(crate::keycode::lookup_media_key(name).is_some(), "{}", name);
+            }
+        }
     }
 }
\ No newline at end of file
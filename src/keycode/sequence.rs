@@ -0,0 +1,142 @@
+use std::fmt;
+use std::ops::Range;
+
+use crate::keycode::parser::{parse_combination, word_spans};
+use crate::keycode::{KeyCombination, ParseError};
+
+/// Chords pressed one after another, such as "Ctrl+X Ctrl+S"
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeySequence {
+    /// Chords in the order they are pressed
+    pub chords: Vec<KeyCombination>,
+    /// Pause in seconds between chords, if the target needs one
+    pub delay: Option<f64>,
+}
+
+impl KeySequence {
+    /// Check whether the sequence is a plain single shortcut
+    pub fn is_single(&self) -> bool {
+        self.chords.len() == 1
+    }
+    
+    /// Key codes of each chord, modifiers first
+    pub fn to_keycodes(&self) -> Vec<Vec<u16>> {
+        self.chords.iter().map(KeyCombination::to_keycodes).collect()
+    }
+    
+    /// Every chord in canonical form, without a delay
+    pub fn canonical(&self) -> KeySequence {
+        KeySequence {
+            chords: self.chords.iter().map(KeyCombination::canonical).collect(),
+            delay: None,
+        }
+    }
+}
+
+impl From<KeyCombination> for KeySequence {
+    fn from(combination: KeyCombination) -> Self {
+        Self { chords: vec![combination], delay: None }
+    }
+}
+
+impl fmt::Display for KeySequence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let chords: Vec<String> = self.chords.iter().map(KeyCombination::to_string).collect();
+        write!(f, "{}", chords.join(" "))
+    }
+}
+
+/// Parse a whitespace-separated sequence of shortcuts
+///
+/// Supports Emacs- and VS Code-style bindings:
+/// - "Ctrl+X Ctrl+S"
+/// - "Cmd+K Cmd+S"
+/// - "Cmd+K S"
+///
+/// Key names may contain spaces ("Page Up"), so a space only starts a new chord
+/// when the text before it is already a complete shortcut and joining the next
+/// word onto it would not be. A single shortcut parses to a one-chord sequence.
+pub fn parse_shortcut_sequence(shortcut: &str) -> Result<KeySequence, ParseError> {
+    // Byte ranges of the chords, each running from its first to its last word
+    let mut groups: Vec<Range<usize>> = Vec::new();
+    
+    for word in word_spans(shortcut) {
+        match groups.last_mut() {
+            Some(group) if continues_chord(&shortcut[group.clone()], &shortcut[group.start..word.end]) => {
+                group.end = word.end;
+            }
+            _ => groups.push(word),
+        }
+    }
+    
+    if groups.is_empty() {
+        return Err(ParseError::EmptyShortcut);
+    }
+    
+    let chords = groups
+        .into_iter()
+        .map(|group| {
+            parse_combination(&shortcut[group.clone()])
+                .map_err(|e| e.offset(group.start).with_suggestions())
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    
+    Ok(KeySequence { chords, delay: None })
+}
+
+/// Check whether the next word belongs to the chord collected so far
+fn continues_chord(current: &str, extended: &str) -> bool {
+    parse_combination(current).is_err() || parse_combination(extended).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_parse_chord_sequence() {
+        let sequence = parse_shortcut_sequence("Ctrl+X Ctrl+S").unwrap();
+        assert_eq!(sequence.chords.len(), 2);
+        assert_eq!(sequence.to_keycodes(), vec![vec![59, 7], vec![59, 1]]);
+        assert_eq!(sequence.to_string(), "Control+X Control+S");
+        
+        let sequence = parse_shortcut_sequence("Cmd+K  S").unwrap();
+        assert_eq!(sequence.to_string(), "Command+K S");
+        assert!(sequence.delay.is_none());
+    }
+    
+    #[test]
+    fn test_key_names_with_spaces() {
+        let sequence = parse_shortcut_sequence("Cmd+Page Up Cmd+Page Down").unwrap();
+        assert_eq!(sequence.to_string(), "Command+PageUp Command+PageDown");
+        
+        let sequence = parse_shortcut_sequence("Page Up Page Down").unwrap();
+        assert_eq!(sequence.chords.len(), 2);
+        
+        // Spaces around separators stay inside one chord
+        let sequence = parse_shortcut_sequence("Cmd + K Cmd + -").unwrap();
+        assert_eq!(sequence.to_string(), "Command+K Command+Minus");
+    }
+    
+    #[test]
+    fn test_single_shortcut_sequence() {
+        let sequence = parse_shortcut_sequence("Cmd+Shift+A").unwrap();
+        assert!(sequence.is_single());
+        assert_eq!(sequence.chords[0], crate::keycode::parse_shortcut("Cmd+Shift+A").unwrap());
+        assert_eq!(KeySequence::from(sequence.chords[0].clone()), sequence);
+    }
+    
+    #[test]
+    fn test_sequence_errors() {
+        assert!(matches!(parse_shortcut_sequence("   "), Err(ParseError::EmptyShortcut)));
+        
+        // Spans point into the whole sequence
+        let err = parse_shortcut_sequence("Ctrl+X  Ctrl+Nope").unwrap_err();
+        assert!(matches!(err, ParseError::UnknownKey { .. }));
+        assert_eq!(err.span(), Some(13..17));
+        assert_eq!(
+            parse_shortcut_sequence("Ctrl+X Cmd"),
+            Err(ParseError::NoMainKey { span: 7..10 })
+        );
+    }
+}
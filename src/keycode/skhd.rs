@@ -0,0 +1,111 @@
+use crate::keycode::{KeyCategory, KeyCode, KeyCombination, Modifiers};
+
+/// skhd modifier names, as (flag, name)
+///
+/// skhd has side-specific names, and `fn` but no Caps Lock.
+const SKHD_MODIFIERS: [(Modifiers, &str); 13] = [
+    (Modifiers::COMMAND, "cmd"),
+    (Modifiers::COMMAND_LEFT, "lcmd"),
+    (Modifiers::COMMAND_RIGHT, "rcmd"),
+    (Modifiers::SHIFT, "shift"),
+    (Modifiers::SHIFT_LEFT, "lshift"),
+    (Modifiers::SHIFT_RIGHT, "rshift"),
+    (Modifiers::OPTION, "alt"),
+    (Modifiers::OPTION_LEFT, "lalt"),
+    (Modifiers::OPTION_RIGHT, "ralt"),
+    (Modifiers::CONTROL, "ctrl"),
+    (Modifiers::CONTROL_LEFT, "lctrl"),
+    (Modifiers::CONTROL_RIGHT, "rctrl"),
+    (Modifiers::FN, "fn"),
+];
+
+/// skhd's literal key names, as (key name, skhd name)
+///
+/// Letters, digits and F-keys are their lowercase name and are not listed.
+/// Any other key is written as its hex key code (`0x2A`), which skhd reads
+/// independently of the keyboard layout.
+const SKHD_KEYS: &[(&str, &str)] = &[
+    ("Return", "return"),
+    ("Tab", "tab"),
+    ("Space", "space"),
+    ("Delete", "backspace"),
+    ("ForwardDelete", "delete"),
+    ("Escape", "escape"),
+    ("Home", "home"),
+    ("End", "end"),
+    ("PageUp", "pageup"),
+    ("PageDown", "pagedown"),
+    ("Help", "insert"),
+    ("LeftArrow", "left"),
+    ("RightArrow", "right"),
+    ("UpArrow", "up"),
+    ("DownArrow", "down"),
+    ("VolumeUp", "sound_up"),
+    ("VolumeDown", "sound_down"),
+    ("Mute", "mute"),
+];
+
+/// The key as skhd writes it, e.g. "a", "f5", "pageup" or "0x2A"
+pub fn skhd_key(key: &KeyCode) -> String {
+    match key.category {
+        KeyCategory::Letters | KeyCategory::Numbers | KeyCategory::FunctionKeys => key.name.to_lowercase(),
+        _ => SKHD_KEYS
+            .iter()
+            .find(|(name, _)| *name == key.name)
+            .map(|(_, skhd)| skhd.to_string())
+            .unwrap_or_else(|| format!("0x{:02X}", key.code)),
+    }
+}
+
+impl KeyCombination {
+    /// An skhd config line running the shell `command`: `cmd + shift - a : command`
+    ///
+    /// None when Caps Lock is held, which skhd has no modifier for.
+    pub fn to_skhd(&self, command: &str) -> Option<String> {
+        if self.modifiers.contains(Modifiers::CAPS_LOCK) {
+            return None;
+        }
+        
+        let modifiers: Vec<&str> = SKHD_MODIFIERS
+            .iter()
+            .filter(|(flag, _)| self.modifiers.contains(*flag))
+            .map(|(_, name)| *name)
+            .collect();
+        let hotkey = if modifiers.is_empty() {
+            skhd_key(&self.key)
+        } else {
+            format!("{} - {}", modifiers.join(" + "), skhd_key(&self.key))
+        };
+        Some(format!("{} : {}", hotkey, command))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keycode::{parse_shortcut, KEY_DATABASE};
+    
+    #[test]
+    fn test_skhd_keys() {
+        let key = |name: &str| skhd_key(KEY_DATABASE.lookup(name).unwrap());
+        assert_eq!(key("A"), "a");
+        assert_eq!(key("7"), "7");
+        assert_eq!(key("F5"), "f5");
+        assert_eq!(key("ForwardDelete"), "delete");
+        assert_eq!(key("Backslash"), "0x2A");
+        assert_eq!(key("Section"), "0x0A");
+        
+        for (name, _) in SKHD_KEYS {
+            assert!(KEY_DATABASE.lookup(name).is_some(), "{}", name);
+        }
+    }
+    
+    #[test]
+    fn test_skhd_line() {
+        let line = |shortcut: &str| parse_shortcut(shortcut).unwrap().to_skhd("open -a Terminal");
+        assert_eq!(line("Cmd+Shift+A").as_deref(), Some("cmd + shift - a : open -a Terminal"));
+        assert_eq!(line("CommandRight+Alt+Return").as_deref(), Some("rcmd + alt - return : open -a Terminal"));
+        assert_eq!(line("F13").as_deref(), Some("f13 : open -a Terminal"));
+        assert!(line("CapsLock+A").is_none());
+    }
+}
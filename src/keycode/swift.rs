@@ -0,0 +1,91 @@
+use crate::keycode::output::PostedEvent;
+use crate::keycode::{KeySequence, Modifiers};
+
+/// `CGEventFlags` mask set while each modifier is down
+const EVENT_FLAGS: [(Modifiers, &str); 6] = [
+    (Modifiers::ANY_COMMAND, ".maskCommand"),
+    (Modifiers::ANY_SHIFT, ".maskShift"),
+    (Modifiers::ANY_OPTION, ".maskAlternate"),
+    (Modifiers::ANY_CONTROL, ".maskControl"),
+    (Modifiers::FN, ".maskSecondaryFn"),
+    (Modifiers::CAPS_LOCK, ".maskAlphaShift"),
+];
+
+/// Helper the generated statements call to post one key event
+const SWIFT_PRELUDE: &str = "\
+import CoreGraphics
+import Foundation
+
+let source = CGEventSource(stateID: .hidSystemState)
+
+func postKey(_ key: CGKeyCode, down: Bool, flags: CGEventFlags) {
+    let event = CGEvent(keyboardEventSource: source, virtualKey: key, keyDown: down)
+    event?.flags = flags
+    event?.post(tap: .cghidEventTap)
+}";
+
+/// The `postKey` call for one event
+fn post_key(event: &PostedEvent) -> String {
+    let masks: Vec<&str> = event.flags
+        .iter()
+        .filter_map(|group| EVENT_FLAGS.iter().find(|(flag, _)| flag == group).map(|(_, mask)| *mask))
+        .collect();
+    format!("postKey({}, down: {}, flags: [{}])", event.code, event.down, masks.join(", "))
+}
+
+impl KeySequence {
+    /// Swift program posting each chord's key events in turn
+    ///
+    /// The sequence's `delay` becomes a `Thread.sleep` between chords.
+    pub fn to_swift(&self) -> String {
+        let mut lines = vec![SWIFT_PRELUDE.to_string()];
+        for (i, chord) in self.chords.iter().enumerate() {
+            lines.push(String::new());
+            if let (Some(seconds), true) = (self.delay, i > 0) {
+                lines.push(format!("Thread.sleep(forTimeInterval: {})", seconds));
+            }
+            lines.push(format!("// {}", chord.to_string()));
+            lines.extend(chord.posted_events().iter().map(post_key));
+        }
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keycode::{parse_shortcut, parse_shortcut_sequence};
+    
+    #[test]
+    fn test_combination_swift() {
+        let swift = KeySequence::from(parse_shortcut("Cmd+Shift+A").unwrap()).to_swift();
+        assert!(swift.starts_with(SWIFT_PRELUDE));
+        assert!(swift.ends_with(
+            "// Command+Shift+A\n\
+             postKey(55, down: true, flags: [.maskCommand])\n\
+             postKey(56, down: true, flags: [.maskCommand, .maskShift])\n\
+             postKey(0, down: true, flags: [.maskCommand, .maskShift])\n\
+             postKey(0, down: false, flags: [.maskCommand, .maskShift])\n\
+             postKey(56, down: false, flags: [.maskCommand])\n\
+             postKey(55, down: false, flags: [])"
+        ));
+    }
+    
+    #[test]
+    fn test_swift_sides_and_fn() {
+        // Both Shift keys set one flag, which stays until the last is released
+        let swift = KeySequence::from(parse_shortcut("ShiftLeft+ShiftRight+F1").unwrap()).to_swift();
+        assert!(swift.contains("postKey(60, down: true, flags: [.maskShift])"));
+        assert!(swift.contains("postKey(60, down: false, flags: [.maskShift])\npostKey(56, down: false, flags: [])"));
+        
+        let swift = KeySequence::from(parse_shortcut("Fn+F1").unwrap()).to_swift();
+        assert!(swift.contains("postKey(63, down: true, flags: [.maskSecondaryFn])"));
+    }
+    
+    #[test]
+    fn test_sequence_swift() {
+        let swift = KeySequence { delay: Some(0.2), ..parse_shortcut_sequence("Ctrl+X Ctrl+S").unwrap() }.to_swift();
+        assert_eq!(swift.matches("// Control+").count(), 2);
+        assert!(swift.contains("postKey(59, down: false, flags: [])\n\nThread.sleep(forTimeInterval: 0.2)\n// Control+S"));
+    }
+}
@@ -0,0 +1,136 @@
+use crate::keycode::{char_to_keystroke, KeyCombination};
+
+/// A single key press needed to type one character
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyEvent {
+    /// The character this press types
+    pub character: char,
+    /// Key and modifiers (Shift for upper case and shifted symbols)
+    pub combination: KeyCombination,
+}
+
+/// A character that has no key on the US layout
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UntypeableChar {
+    pub character: char,
+    /// Position of the character in the input, counted in chars
+    pub index: usize,
+}
+
+/// Ordered key presses that type a piece of text
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TypingPlan {
+    pub events: Vec<KeyEvent>,
+    /// Characters that were skipped because no key types them
+    pub untypeable: Vec<UntypeableChar>,
+}
+
+/// Convert text into the key presses that type it on the US layout
+///
+/// Newlines become Return (a "\r\n" pair is one Return), tabs become Tab, and
+/// upper-case letters and shifted symbols carry a Shift modifier. Characters
+/// without a key are reported in `untypeable` and left out of `events`.
+pub fn text_to_keystrokes(text: &str) -> TypingPlan {
+    let mut plan = TypingPlan::default();
+    let mut chars = text.chars().enumerate().peekable();
+    
+    while let Some((index, ch)) = chars.next() {
+        if ch == '\r' && matches!(chars.peek(), Some((_, '\n'))) {
+            continue;
+        }
+        
+        match char_to_keystroke(ch) {
+            Some(combination) => plan.events.push(KeyEvent { character: ch, combination }),
+            None => plan.untypeable.push(UntypeableChar { character: ch, index }),
+        }
+    }
+    
+    plan
+}
+
+impl TypingPlan {
+    /// Check that every character of the input can be typed
+    pub fn is_complete(&self) -> bool {
+        self.untypeable.is_empty()
+    }
+    
+    /// Generate a System Events script pressing each key in order
+    ///
+    /// `delay` inserts a pause (in seconds) after every key press, which helps
+    /// with slow targets such as remote desktop sessions.
+    pub fn to_applescript(&self, delay: Option<f64>) -> String {
+        let mut lines = vec!["tell application \"System Events\"".to_string()];
+        
+        for event in &self.events {
+            lines.push(format!(
+                "    {} -- {}",
+                event.combination.applescript_command(),
+                describe_char(event.character)
+            ));
+            if let Some(seconds) = delay {
+                lines.push(format!("    delay {}", seconds));
+            }
+        }
+        
+        lines.push("end tell".to_string());
+        lines.join("\n")
+    }
+}
+
+/// Readable form of a character for script comments
+fn describe_char(ch: char) -> String {
+    match ch {
+        '\n' | '\r' => "newline".to_string(),
+        '\t' => "tab".to_string(),
+        ' ' => "space".to_string(),
+        _ => format!("\"{}\"", ch),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keycode::Modifiers;
+    
+    #[test]
+    fn test_text_to_keystrokes() {
+        let plan = text_to_keystrokes("Hi!");
+        assert!(plan.is_complete());
+        assert_eq!(plan.events.len(), 3);
+        
+        assert_eq!(plan.events[0].combination.key.name, "H");
+        assert_eq!(plan.events[0].combination.modifiers, Modifiers::SHIFT);
+        assert!(plan.events[1].combination.modifiers.is_empty());
+        assert_eq!(plan.events[2].combination.key.name, "1");
+    }
+    
+    #[test]
+    fn test_whitespace() {
+        let plan = text_to_keystrokes("a\tb\r\nc\n");
+        let names: Vec<_> = plan.events.iter().map(|e| e.combination.key.name).collect();
+        assert_eq!(names, vec!["A", "Tab", "B", "Return", "C", "Return"]);
+    }
+    
+    #[test]
+    fn test_untypeable_characters() {
+        let plan = text_to_keystrokes("café €5");
+        assert!(!plan.is_complete());
+        assert_eq!(plan.untypeable, vec![
+            UntypeableChar { character: 'é', index: 3 },
+            UntypeableChar { character: '€', index: 5 },
+        ]);
+        assert_eq!(plan.events.len(), 5);
+    }
+    
+    #[test]
+    fn test_to_applescript() {
+        let script = text_to_keystrokes("A?").to_applescript(None);
+        assert_eq!(script, "tell application \"System Events\"\n    \
+            key code 0 using {shift down} -- \"A\"\n    \
+            key code 44 using {shift down} -- \"?\"\n\
+            end tell");
+        
+        let script = text_to_keystrokes("a").to_applescript(Some(0.05));
+        assert!(script.contains("    delay 0.05"));
+    }
+}
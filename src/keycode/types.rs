@@ -2,16 +2,128 @@ use serde::{Deserialize, Serialize};
 use strum_macros::{Display, EnumString};
 
 /// Represents a single keyboard key with its AppleScript code
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+///
+/// Keys are `'static` records: the embedded data is compiled into static
+/// tables by `build.rs`, and an override file is loaded once and kept for the
+/// life of the process.
+#[derive(Debug, Clone, Serialize, PartialEq)]
 pub struct KeyCode {
     /// Display name of the key
-    pub name: String,
+    pub name: &'static str,
     /// AppleScript key code
     pub code: u16,
     /// Category this key belongs to
     pub category: KeyCategory,
     /// Alternative names/aliases for this key
-    pub aliases: Vec<String>,
+    pub aliases: &'static [&'static str],
+    /// Character typed with Shift held on the US layout (e.g. `{` for LeftBracket)
+    pub shifted: Option<&'static str>,
+    /// Physical layouts that have this key
+    pub layouts: &'static [Layout],
+    /// Carbon virtual key constant from `HIToolbox/Events.h` (e.g. `kVK_ANSI_A`)
+    pub carbon_constant: Option<&'static str>,
+    /// Glyph Apple uses for the key in menus (e.g. ⌫, ⎋, ⇞)
+    pub glyph: Option<&'static str>,
+    /// USB HID usage reported by the keyboard
+    pub hid_usage: Option<HidUsage>,
+    /// Human-readable description
+    pub description: Option<&'static str>,
+    /// Whether the key types a visible character
+    pub printable: bool,
+    /// Where the code comes from and how well it has been verified
+    pub provenance: Option<Provenance>,
+}
+
+/// USB HID usage page and id for a key
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(deny_unknown_fields)]
+pub struct HidUsage {
+    /// Usage page (0x07 keyboard/keypad, 0xFF Apple vendor)
+    pub page: u16,
+    /// Usage id within the page
+    pub id: u16,
+}
+
+impl std::fmt::Display for HidUsage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "0x{:02X}:0x{:02X}", self.page, self.id)
+    }
+}
+
+/// Origin and verification status of a key code
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+pub struct Provenance {
+    /// Where the code was taken from
+    pub source: CodeSource,
+    /// How the code was last verified, if at all
+    pub verified_by: Option<VerificationMethod>,
+    /// macOS version of the last verification (e.g. "14.5")
+    pub macos_version: Option<&'static str>,
+    /// Keyboard model used for the last verification
+    pub keyboard: Option<&'static str>,
+    /// Date of the last verification (YYYY-MM-DD)
+    pub last_verified: Option<&'static str>,
+    /// How far the code can be trusted
+    pub confidence: Confidence,
+}
+
+/// Where a key code was taken from
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Display)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum CodeSource {
+    /// `kVK_*` constant in `HIToolbox/Events.h`
+    AppleHeader,
+    /// Read from a key event viewer on real hardware
+    Observed,
+    /// Third-party documentation
+    Documentation,
+    /// Derived from neighbouring keys, never checked
+    Inferred,
+}
+
+/// How a key code was verified
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Display)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum VerificationMethod {
+    /// `scripts/verify_keycodes.py`: System Events accepted the code
+    AutomatedTest,
+    /// `verify` tool session: a person confirmed the key that was typed
+    ManualTest,
+}
+
+/// Trust level of a key code
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash, Display)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum Confidence {
+    /// Sources disagree or the code was never checked
+    Low,
+    /// Documented by Apple but not tested
+    Medium,
+    /// Documented and confirmed by a test
+    High,
+}
+
+/// Physical keyboard layouts shipped by Apple
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash, Display, EnumString)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+pub enum Layout {
+    /// US-style layout (wide Return key, no Section key)
+    Ansi,
+    /// European layout with the extra Section (§) key
+    Iso,
+    /// Japanese layout with Yen, Underscore, Eisu and Kana keys
+    Jis,
+}
+
+impl Layout {
+    /// All known layouts
+    pub const fn all() -> &'static [Layout] {
+        &[Layout::Ansi, Layout::Iso, Layout::Jis]
+    }
 }
 
 /// Categories for organizing keyboard keys
@@ -35,31 +147,62 @@ pub enum KeyCategory {
     NumpadKeys,
     /// Punctuation and symbol keys
     Punctuation,
+    /// Media and system keys (volume, brightness, playback)
+    MediaKeys,
 }
 
 impl KeyCode {
     /// Create a new KeyCode
-    pub fn new(name: impl Into<String>, code: u16, category: KeyCategory) -> Self {
+    pub const fn new(name: &'static str, code: u16, category: KeyCategory) -> Self {
         Self {
-            name: name.into(),
+            name,
             code,
             category,
-            aliases: Vec::new(),
+            aliases: &[],
+            shifted: None,
+            layouts: Layout::all(),
+            carbon_constant: None,
+            glyph: None,
+            hid_usage: None,
+            description: None,
+            printable: false,
+            provenance: None,
         }
     }
     
-    /// Add an alias to this key
-    pub fn with_alias(mut self, alias: impl Into<String>) -> Self {
-        self.aliases.push(alias.into());
+    /// Set the aliases of this key
+    pub const fn with_aliases(mut self, aliases: &'static [&'static str]) -> Self {
+        self.aliases = aliases;
+        self
+    }
+    
+    /// Set the character this key types with Shift
+    pub const fn with_shifted(mut self, shifted: &'static str) -> Self {
+        self.shifted = Some(shifted);
         self
     }
     
-    /// Add multiple aliases
-    pub fn with_aliases(mut self, aliases: Vec<String>) -> Self {
-        self.aliases.extend(aliases);
+    /// Restrict this key to specific layouts
+    pub const fn with_layouts(mut self, layouts: &'static [Layout]) -> Self {
+        self.layouts = layouts;
         self
     }
     
+    /// Confidence in the key code; keys without provenance count as low
+    pub fn confidence(&self) -> Confidence {
+        self.provenance.map_or(Confidence::Low, |p| p.confidence)
+    }
+    
+    /// Check if this key exists on the given layout
+    pub fn available_on(&self, layout: Layout) -> bool {
+        self.layouts.contains(&layout)
+    }
+    
+    /// Check if this key only exists on some layouts
+    pub fn is_layout_specific(&self) -> bool {
+        Layout::all().iter().any(|layout| !self.available_on(*layout))
+    }
+    
     /// Check if this key matches a given name (case-insensitive)
     pub fn matches(&self, query: &str) -> bool {
         let query_lower = query.to_lowercase();
@@ -91,8 +234,7 @@ mod tests {
     #[test]
     fn test_keycode_with_aliases() {
         let key = KeyCode::new("Command", 55, KeyCategory::ModifierKeys)
-            .with_alias("Cmd")
-            .with_alias("⌘");
+            .with_aliases(&["Cmd", "⌘"]);
         
         assert_eq!(key.aliases.len(), 2);
         assert!(key.matches("Command"));
@@ -100,6 +242,33 @@ mod tests {
         assert!(key.matches("⌘"));
     }
     
+    #[test]
+    fn test_keycode_layouts() {
+        let key = KeyCode::new("A", 0, KeyCategory::Letters);
+        assert!(key.available_on(Layout::Iso));
+        assert!(!key.is_layout_specific());
+        
+        let key = KeyCode::new("Section", 10, KeyCategory::Punctuation)
+            .with_layouts(&[Layout::Iso]);
+        assert!(key.available_on(Layout::Iso));
+        assert!(!key.available_on(Layout::Ansi));
+        assert!(key.is_layout_specific());
+        
+        assert_eq!("JIS".parse::<Layout>().unwrap(), Layout::Jis);
+        assert_eq!(Layout::Ansi.to_string(), "ansi");
+    }
+    
+    #[test]
+    fn test_shifted_symbol_is_not_an_alias() {
+        let key = KeyCode::new("LeftBracket", 33, KeyCategory::Punctuation)
+            .with_aliases(&["["])
+            .with_shifted("{");
+        
+        assert!(key.matches("["));
+        assert!(!key.matches("{"));
+        assert_eq!(key.shifted, Some("{"));
+    }
+    
     #[test]
     fn test_category_display() {
         assert_eq!(KeyCategory::Letters.to_string(), "letters");
@@ -8,7 +8,7 @@ pub mod search;
 pub mod utils;
 pub mod mcp_server;
 
-pub use keycode::{KeyCode, KeyCategory, KEY_DATABASE};
+pub use keycode::{KeyCode, KeyCategory, Layout, KEY_DATABASE};
 pub use mcp::MacKeyboardServer;
 pub use search::FuzzySearcher;
 
@@ -58,17 +58,17 @@ mod tests {
     fn test_shortcut_parsing() {
         // Simple shortcuts
         let combo1 = parse_shortcut("Cmd+A").unwrap();
-        assert_eq!(combo1.modifiers.len(), 1);
+        assert_eq!(combo1.modifiers.names().len(), 1);
         assert_eq!(combo1.key.name, "A");
         
         // Multiple modifiers
         let combo2 = parse_shortcut("Cmd+Shift+S").unwrap();
-        assert_eq!(combo2.modifiers.len(), 2);
+        assert_eq!(combo2.modifiers.names().len(), 2);
         assert_eq!(combo2.key.name, "S");
         
         // Symbol shortcuts
         let combo3 = parse_shortcut("⌘⇧A").unwrap();
-        assert_eq!(combo3.modifiers.len(), 2);
+        assert_eq!(combo3.modifiers.names().len(), 2);
         assert_eq!(combo3.key.name, "A");
     }
     
@@ -10,6 +10,7 @@ mod mcp;
 mod search;
 mod utils;
 
+use crate::keycode::DataSource;
 use crate::mcp::MacKeyboardServer;
 
 #[derive(Debug, Deserialize)]
@@ -56,6 +57,10 @@ async fn main() -> Result<()> {
     
     info!("Starting Mac Keyboard MCP server v{}", env!("CARGO_PKG_VERSION"));
     
+    // Load key data from --keycodes, MAC_KEYBOARD_MCP_KEYCODES or the embedded file
+    let source = data_source_from_args(std::env::args().skip(1))?;
+    keycode::database::initialize(source)?;
+    
     // Create the MCP server
     let server = MacKeyboardServer::new()?;
     
@@ -149,6 +154,23 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
+/// Pick the key data source from command-line arguments (`--keycodes <path>`)
+fn data_source_from_args(mut args: impl Iterator<Item = String>) -> Result<DataSource> {
+    while let Some(arg) = args.next() {
+        if arg == "--keycodes" {
+            let path = args
+                .next()
+                .ok_or_else(|| anyhow::anyhow!("--keycodes requires a file path"))?;
+            return Ok(DataSource::File(path.into()));
+        }
+        if let Some(path) = arg.strip_prefix("--keycodes=") {
+            return Ok(DataSource::File(path.into()));
+        }
+    }
+    
+    Ok(DataSource::from_env())
+}
+
 async fn handle_request(server: &MacKeyboardServer, request: JsonRpcRequest) -> Option<JsonRpcResponse> {
     // Handle case where method might be None
     let method = match request.method {
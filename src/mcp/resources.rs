@@ -2,14 +2,16 @@ use anyhow::Result;
 use serde_json::{json, Value};
 use tracing::debug;
 
-use crate::keycode::{KEY_DATABASE, KeyCategory};
+use crate::keycode::{KEY_DATABASE, KeyCategory, Layout};
+use crate::keycode::media_keys::MEDIA_KEYS;
+use crate::mcp::tools::key_json;
 
 /// Resource definition for all keycodes
 pub fn all_keycodes_resource() -> Value {
     json!({
         "uri": "keycode://all",
         "name": "All Key Codes",
-        "description": "Complete database of AppleScript key codes",
+        "description": "Complete database of AppleScript key codes (append ?layout=ansi|iso|jis to filter by physical layout)",
         "mimeType": "application/json"
     })
 }
@@ -75,6 +77,12 @@ pub fn category_resources() -> Vec<Value> {
             "description": "Punctuation and symbol keys",
             "mimeType": "application/json"
         }),
+        json!({
+            "uri": "keycode://category/media_keys",
+            "name": "Media Keys",
+            "description": "Media and system keys (volume, brightness, playback) with NX_KEYTYPE values",
+            "mimeType": "application/json"
+        }),
     ]
 }
 
@@ -83,38 +91,38 @@ pub async fn read_resource_content(uri: &str) -> Result<Value> {
     debug!("Reading resource: {}", uri);
     
     match uri {
-        "keycode://all" => {
-            // Return all keys organized by category
-            let mut all_keys = json!({});
+        "keycode://all" => Ok(all_keys_content(None)),
+        
+        uri if uri.starts_with("keycode://all?layout=") => {
+            let layout_name = uri.strip_prefix("keycode://all?layout=")
+                .ok_or_else(|| anyhow::anyhow!("Invalid layout URI"))?;
             
-            for (category, _) in KEY_DATABASE.categories() {
-                let keys = KEY_DATABASE.by_category(category);
-                let category_data: Vec<Value> = keys.into_iter().map(|key| {
-                    json!({
-                        "name": key.name,
-                        "code": key.code,
-                        "aliases": key.aliases,
-                    })
-                }).collect();
-                
-                all_keys[category.to_string()] = json!(category_data);
-            }
+            let layout = layout_name.parse::<Layout>()
+                .map_err(|_| anyhow::anyhow!("Unknown layout: {}", layout_name))?;
             
-            Ok(json!({
-                "totalKeys": KEY_DATABASE.all_keys().len(),
-                "categories": all_keys,
-            }))
+            Ok(all_keys_content(Some(layout)))
         }
         
         "keycode://aliases" => {
             // Build alias map
             let mut aliases = json!({});
+            let mut shifted = json!({});
             
             for key in KEY_DATABASE.all_keys() {
-                for alias in &key.aliases {
+                for alias in key.aliases {
                     aliases[alias] = json!({
                         "canonical": key.name,
                         "code": key.code,
+                        "glyph": key.glyph,
+                    });
+                }
+                
+                // Shifted symbols are not aliases: they need Shift held
+                if let Some(symbol) = &key.shifted {
+                    shifted[symbol] = json!({
+                        "base_key": key.name,
+                        "code": key.code,
+                        "shortcut": format!("Shift+{}", key.name),
                     });
                 }
             }
@@ -122,6 +130,7 @@ pub async fn read_resource_content(uri: &str) -> Result<Value> {
             Ok(json!({
                 "aliases": aliases,
                 "count": aliases.as_object().unwrap().len(),
+                "shifted": shifted,
             }))
         }
         
@@ -133,21 +142,67 @@ pub async fn read_resource_content(uri: &str) -> Result<Value> {
                 .map_err(|_| anyhow::anyhow!("Unknown category: {}", category_name))?;
             
             let keys = KEY_DATABASE.by_category(category);
-            let key_data: Vec<Value> = keys.into_iter().map(|key| {
-                json!({
-                    "name": key.name,
-                    "code": key.code,
-                    "aliases": key.aliases,
-                })
-            }).collect();
+            let key_data: Vec<Value> = keys.into_iter().map(key_json).collect();
             
-            Ok(json!({
+            let mut content = json!({
                 "category": category.to_string(),
                 "count": key_data.len(),
                 "keys": key_data,
-            }))
+            });
+            
+            if category == KeyCategory::MediaKeys {
+                // Media keys without a virtual key code only exist as system-defined events
+                content["system_defined"] = json!(MEDIA_KEYS.iter().map(|media| {
+                    json!({
+                        "name": media.name,
+                        "nx_keytype": media.nx_keytype,
+                        "nx_constant": media.nx_constant,
+                        "key_code": media.key_code,
+                        "triggerable_by_key_code": media.triggerable_by_key_code,
+                    })
+                }).collect::<Vec<_>>());
+            }
+            
+            Ok(content)
         }
         
         _ => Err(anyhow::anyhow!("Unknown resource URI: {}", uri)),
     }
+}
+
+/// Build the `keycode://all` content, optionally limited to one physical layout
+fn all_keys_content(layout: Option<Layout>) -> Value {
+    // Return all keys organized by category
+    let mut all_keys = json!({});
+    let mut total_keys = 0;
+    
+    for (category, _) in KEY_DATABASE.categories() {
+        let keys = KEY_DATABASE.by_category(category);
+        let category_data: Vec<Value> = keys.into_iter()
+            .filter(|key| layout.is_none_or(|l| key.available_on(l)))
+            .map(key_json)
+            .collect();
+        
+        total_keys += category_data.len();
+        all_keys[category.to_string()] = json!(category_data);
+    }
+    
+    // Keys that only exist on some layouts, grouped by layout
+    let mut layout_specific = json!({});
+    for &l in Layout::all() {
+        let mut names: Vec<_> = KEY_DATABASE.by_layout(l)
+            .into_iter()
+            .filter(|key| key.is_layout_specific())
+            .map(|key| key.name)
+            .collect();
+        names.sort();
+        layout_specific[l.to_string()] = json!(names);
+    }
+    
+    json!({
+        "layout": layout.map(|l| l.to_string()),
+        "totalKeys": total_keys,
+        "categories": all_keys,
+        "layoutSpecific": layout_specific,
+    })
 }
\ No newline at end of file
@@ -36,8 +36,13 @@ impl MacKeyboardServer {
     pub fn list_tools(&self) -> Value {
         json!([
             tools::lookup_keycode_tool(),
+            tools::reverse_lookup_keycode_tool(),
             tools::search_keys_tool(),
             tools::get_key_combinations_tool(),
+            tools::char_to_keystroke_tool(),
+            tools::text_to_keycodes_tool(),
+            tools::normalize_shortcut_tool(),
+            tools::karabiner_rule_tool(),
             tools::list_categories_tool(),
         ])
     }
@@ -54,8 +59,13 @@ impl MacKeyboardServer {
     pub async fn call_tool(&self, name: &str, arguments: Value) -> Result<Value> {
         match name {
             "lookup_keycode" => tools::handle_lookup(arguments).await,
+            "reverse_lookup_keycode" => tools::handle_reverse_lookup(arguments).await,
             "search_keys" => tools::handle_search(arguments).await,
             "get_key_combinations" => tools::handle_combinations(arguments).await,
+            "char_to_keystroke" => tools::handle_char_to_keystroke(arguments).await,
+            "text_to_keycodes" => tools::handle_text_to_keycodes(arguments).await,
+            "normalize_shortcut" => tools::handle_normalize(arguments).await,
+            "karabiner_rule" => tools::handle_karabiner_rule(arguments).await,
             "list_categories" => tools::handle_categories(arguments).await,
             _ => Err(anyhow::anyhow!("Unknown tool: {}", name)),
         }
@@ -1,9 +1,10 @@
 use anyhow::Result;
 use serde::Deserialize;
 use serde_json::{json, Value};
+use std::collections::BTreeMap;
 use tracing::debug;
 
-use crate::keycode::{KEY_DATABASE, KeyCategory, parse_shortcut, is_secondary_function_query, get_secondary_function};
+use crate::keycode::{KEY_DATABASE, Confidence, KarabinerManipulator, KarabinerRule, KeyCategory, KeyCode, KeyCombination, KeySequence, Gesture, Layout, MediaKey, Modifiers, fn_layer_key, fn_layer_source, Notation, OutputFormat, ParseError, ShortcutStyle, detect_notation, is_gesture, parse_any_shortcut, parse_gesture, char_to_keystroke, is_secondary_function_query, get_secondary_function, lookup_media_key, media_key_for_code, text_to_keystrokes};
 use crate::search::FuzzySearcher;
 
 /// Tool definition for lookup_keycode
@@ -16,12 +17,22 @@ pub fn lookup_keycode_tool() -> Value {
             "properties": {
                 "key_name": {
                     "type": "string",
-                    "description": "Name of the key (e.g., 'A', 'Space', 'F1', 'F3+' for F3's secondary function)"
+                    "description": "Name of the key (e.g., 'A', 'Space', 'F1', 'F3+' for F3's secondary function), or a numeric key code like '49' for a reverse lookup"
                 },
                 "fuzzy": {
                     "type": "boolean",
                     "description": "Enable fuzzy matching for typos",
                     "default": false
+                },
+                "layout": {
+                    "type": "string",
+                    "description": "Physical keyboard layout to check the key against",
+                    "enum": ["ansi", "iso", "jis"]
+                },
+                "output_format": {
+                    "type": "string",
+                    "description": "Also return a script pressing the key in this language",
+                    "enum": ["applescript", "jxa", "swift", "python", "pyautogui", "hammerspoon"]
                 }
             },
             "required": ["key_name"]
@@ -29,6 +40,25 @@ pub fn lookup_keycode_tool() -> Value {
     })
 }
 
+/// Tool definition for reverse_lookup_keycode
+pub fn reverse_lookup_keycode_tool() -> Value {
+    json!({
+        "name": "reverse_lookup_keycode",
+        "description": "Find every key that uses a given AppleScript key code (e.g. to decode 'key code 56' in an existing script)",
+        "inputSchema": {
+            "type": "object",
+            "properties": {
+                "code": {
+                    "type": "integer",
+                    "description": "AppleScript key code (e.g. 49 for Space)",
+                    "minimum": 0
+                }
+            },
+            "required": ["code"]
+        }
+    })
+}
+
 /// Tool definition for search_keys
 pub fn search_keys_tool() -> Value {
     json!({
@@ -45,7 +75,13 @@ pub fn search_keys_tool() -> Value {
                     "type": "string",
                     "description": "Filter by category (letters, numbers, function_keys, etc.)",
                     "enum": ["letters", "numbers", "function_keys", "modifier_keys", 
-                             "navigation_keys", "special_keys", "numpad_keys", "punctuation"]
+                             "navigation_keys", "special_keys", "numpad_keys", "punctuation",
+                             "media_keys"]
+                },
+                "layout": {
+                    "type": "string",
+                    "description": "Only return keys present on this physical layout",
+                    "enum": ["ansi", "iso", "jis"]
                 }
             }
         }
@@ -56,13 +92,37 @@ pub fn search_keys_tool() -> Value {
 pub fn get_key_combinations_tool() -> Value {
     json!({
         "name": "get_key_combinations",
-        "description": "Generate key code sequences for shortcuts",
+        "description": "Generate key code sequences for shortcuts, including multi-chord bindings like 'Ctrl+X Ctrl+S' and Emacs, Vim, VS Code, Electron or Cocoa notation",
         "inputSchema": {
             "type": "object",
             "properties": {
                 "shortcut": {
                     "type": "string",
-                    "description": "Shortcut string (e.g., 'Cmd+A', 'Ctrl+Shift+F5', '⌘⇧A')"
+                    "description": "Shortcut string (e.g., 'Cmd+A', 'Ctrl+Shift+F5', '⌘⇧A'), space-separated chords (e.g., 'Cmd+K Cmd+S'), or a modifier-only gesture (e.g., 'Fn', 'double-tap Control', 'hold Option for 2s')"
+                },
+                "notation": {
+                    "type": "string",
+                    "description": "Notation the shortcut is written in; detected from the shortcut when omitted",
+                    "enum": ["native", "emacs", "vim", "vscode", "electron", "cocoa"]
+                },
+                "style": {
+                    "type": "string",
+                    "description": "Also render the shortcut in this style (e.g. 'glyphs' for ⌃⌥⇧⌘A, 'html' for <kbd> markup, 'spoken' for screen readers)",
+                    "enum": ["long", "short", "glyphs", "emacs", "vscode", "electron", "html", "spoken"]
+                },
+                "delay": {
+                    "type": "number",
+                    "description": "Optional pause in seconds between chords"
+                },
+                "output_format": {
+                    "type": "string",
+                    "description": "Also return a script pressing the shortcut in this language",
+                    "enum": ["applescript", "jxa", "swift", "python", "pyautogui", "hammerspoon"]
+                },
+                "bindings": {
+                    "type": "boolean",
+                    "description": "Also return Hammerspoon and skhd hotkey bindings for each chord",
+                    "default": false
                 }
             },
             "required": ["shortcut"]
@@ -70,6 +130,111 @@ pub fn get_key_combinations_tool() -> Value {
     })
 }
 
+/// Tool definition for char_to_keystroke
+pub fn char_to_keystroke_tool() -> Value {
+    json!({
+        "name": "char_to_keystroke",
+        "description": "Find the key and modifiers that type a character on the US layout (e.g. '?' is Shift+Slash)",
+        "inputSchema": {
+            "type": "object",
+            "properties": {
+                "character": {
+                    "type": "string",
+                    "description": "A single character (e.g. '?', 'A', '@')"
+                }
+            },
+            "required": ["character"]
+        }
+    })
+}
+
+/// Tool definition for text_to_keycodes
+pub fn text_to_keycodes_tool() -> Value {
+    json!({
+        "name": "text_to_keycodes",
+        "description": "Convert text into the ordered key presses that type it on the US layout, with an AppleScript that replays them",
+        "inputSchema": {
+            "type": "object",
+            "properties": {
+                "text": {
+                    "type": "string",
+                    "description": "Text to type; newlines become Return and tabs become Tab"
+                },
+                "delay": {
+                    "type": "number",
+                    "description": "Optional pause in seconds after each key press"
+                }
+            },
+            "required": ["text"]
+        }
+    })
+}
+
+/// Tool definition for normalize_shortcut
+pub fn normalize_shortcut_tool() -> Value {
+    json!({
+        "name": "normalize_shortcut",
+        "description": "Reduce shortcuts to a canonical form so they can be compared and deduplicated (e.g. 'Shift+Cmd+A', '⌘⇧A' and 'cmd+shift+a' are all 'Command+Shift+A')",
+        "inputSchema": {
+            "type": "object",
+            "properties": {
+                "shortcuts": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Shortcuts to normalize, in any supported notation"
+                },
+                "notation": {
+                    "type": "string",
+                    "description": "Notation of every shortcut; detected per shortcut when omitted",
+                    "enum": ["native", "emacs", "vim", "vscode", "electron", "cocoa"]
+                }
+            },
+            "required": ["shortcuts"]
+        }
+    })
+}
+
+/// Tool definition for karabiner_rule
+pub fn karabiner_rule_tool() -> Value {
+    json!({
+        "name": "karabiner_rule",
+        "description": "Build a Karabiner-Elements complex_modifications rule that remaps shortcuts (e.g. 'Cmd+Shift+A' to 'Ctrl+B')",
+        "inputSchema": {
+            "type": "object",
+            "properties": {
+                "mappings": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "from": {
+                                "type": "string",
+                                "description": "Shortcut to remap, a single chord (e.g. 'Cmd+Shift+A')"
+                            },
+                            "to": {
+                                "type": "string",
+                                "description": "Shortcut to send instead; a sequence like 'Ctrl+X Ctrl+S' sends each chord in turn"
+                            }
+                        },
+                        "required": ["from", "to"]
+                    },
+                    "description": "Shortcut pairs, one manipulator each"
+                },
+                "description": {
+                    "type": "string",
+                    "description": "Rule description shown in Karabiner-Elements; built from the mappings when omitted"
+                },
+                "notation": {
+                    "type": "string",
+                    "description": "Notation of every shortcut; detected per shortcut when omitted",
+                    "enum": ["native", "emacs", "vim", "vscode", "electron", "cocoa"]
+                }
+            },
+            "required": ["mappings"]
+        }
+    })
+}
+
 /// Tool definition for list_categories
 pub fn list_categories_tool() -> Value {
     json!({
@@ -87,6 +252,93 @@ struct LookupArgs {
     key_name: String,
     #[serde(default)]
     fuzzy: bool,
+    layout: Option<String>,
+    output_format: Option<String>,
+}
+
+/// Warning for keys whose code has not been confirmed by a test
+fn verification_warning(key: &KeyCode) -> Option<String> {
+    match key.confidence() {
+        Confidence::High => None,
+        Confidence::Medium => Some(format!(
+            "Key code {} for {} comes from Apple's headers but has not been tested; verify it before relying on it",
+            key.code, key.name
+        )),
+        Confidence::Low => Some(format!(
+            "Key code {} for {} is unverified or disagrees with Apple's headers; test it on the target Mac before use",
+            key.code, key.name
+        )),
+    }
+}
+
+/// Parse an optional layout argument, returning an error response for unknown layouts
+fn parse_layout(layout: Option<&str>) -> std::result::Result<Option<Layout>, Value> {
+    match layout {
+        Some(name) => name.parse::<Layout>().map(Some).map_err(|_| {
+            json!({
+                "error": format!("Invalid layout: {}", name),
+                "valid_layouts": ["ansi", "iso", "jis"]
+            })
+        }),
+        None => Ok(None),
+    }
+}
+
+/// Parse an optional notation argument, returning an error response for unknown notations
+fn parse_notation(notation: Option<&str>) -> std::result::Result<Option<Notation>, Value> {
+    match notation {
+        Some(name) => name.parse::<Notation>().map(Some).map_err(|_| {
+            json!({
+                "success": false,
+                "error": format!("Invalid notation: {}", name),
+                "valid_notations": Notation::all().iter().map(Notation::to_string).collect::<Vec<_>>()
+            })
+        }),
+        None => Ok(None),
+    }
+}
+
+/// Parse an optional output style argument, returning an error response for unknown styles
+fn parse_style(style: Option<&str>) -> std::result::Result<Option<ShortcutStyle>, Value> {
+    match style {
+        Some(name) => name.parse::<ShortcutStyle>().map(Some).map_err(|_| {
+            json!({
+                "success": false,
+                "error": format!("Invalid style: {}", name),
+                "valid_styles": ShortcutStyle::all().iter().map(ShortcutStyle::to_string).collect::<Vec<_>>()
+            })
+        }),
+        None => Ok(None),
+    }
+}
+
+fn parse_output_format(format: Option<&str>) -> std::result::Result<Option<OutputFormat>, Value> {
+    match format {
+        Some(name) => name.parse::<OutputFormat>().map(Some).map_err(|_| {
+            json!({
+                "success": false,
+                "error": format!("Invalid output format: {}", name),
+                "valid_output_formats": OutputFormat::all().iter().map(OutputFormat::to_string).collect::<Vec<_>>()
+            })
+        }),
+        None => Ok(None),
+    }
+}
+
+/// Add the generated `script`, or why the format cannot press these keys
+fn add_script(response: &mut Value, format: OutputFormat, script: Option<String>) {
+    response["output_format"] = json!(format);
+    if script.is_none() {
+        let reason = match format {
+            OutputFormat::Hammerspoon => "cannot hold Caps Lock as a modifier, or a modifier with a media key",
+            _ => "has no name for one of these keys",
+        };
+        response["script_error"] = json!(format!(
+            "{} {}; the python output format presses every key code",
+            format, reason
+        ));
+    }
+    response["script"] = json!(script);
 }
 
 /// Handle lookup_keycode tool call
@@ -94,12 +346,19 @@ pub async fn handle_lookup(args: Value) -> Result<Value> {
     let args: LookupArgs = serde_json::from_value(args)?;
     debug!("Looking up key: {}", args.key_name);
     
+    let layout = match parse_layout(args.layout.as_deref()) {
+        Ok(layout) => layout,
+        Err(response) => return Ok(response),
+    };
+    let output_format = match parse_output_format(args.output_format.as_deref()) {
+        Ok(format) => format,
+        Err(response) => return Ok(response),
+    };
+    
     // Check if this is a secondary function query (e.g., "F3+")
     if let Some(f_key) = is_secondary_function_query(&args.key_name) {
-        if let Some(secondary) = get_secondary_function(f_key) {
-            // Also get the primary F-key info
-            let primary = KEY_DATABASE.lookup(f_key).unwrap();
-            
+        // Also get the primary F-key info, which a --keycodes override may have dropped
+        if let (Some(secondary), Some(primary)) = (get_secondary_function(f_key), KEY_DATABASE.lookup(f_key)) {
             return Ok(json!({
                 "found": true,
                 "query_type": "secondary_function",
@@ -109,11 +368,16 @@ pub async fn handle_lookup(args: Value) -> Result<Value> {
                         "name": primary.name,
                         "code": primary.code,
                         "description": format!("Traditional {} function key", f_key),
+                        "key": key_json(primary),
+                        "applescript": KeyCombination::from(primary).to_applescript(),
                     },
                     "secondary_function": {
                         "name": secondary.name,
                         "code": secondary.keycode,
                         "description": secondary.description,
+                        "media_key": secondary.media_key
+                            .and_then(lookup_media_key)
+                            .map(media_key_json),
                     },
                     "note": format!(
                         "On modern Mac keyboards, pressing {} triggers '{}' by default. \
@@ -127,15 +391,103 @@ pub async fn handle_lookup(args: Value) -> Result<Value> {
     
     // Regular lookup
     if let Some(keycode) = KEY_DATABASE.lookup(&args.key_name) {
-        Ok(json!({
+        let mut response = json!({
             "found": true,
-            "key": {
-                "name": keycode.name,
-                "code": keycode.code,
-                "category": keycode.category.to_string(),
-                "aliases": keycode.aliases,
+            "key": key_json(keycode),
+            "applescript": KeyCombination::from(keycode).to_applescript(),
+        });
+        if let Some(media) = untriggerable_media_key(keycode) {
+            withhold_applescript(&mut response, media);
+        }
+        
+        if let Some(format) = output_format {
+            add_script(&mut response, format, KeyCombination::from(keycode).to_script(format));
+        }
+        
+        if let Some(warning) = verification_warning(keycode) {
+            response["verification_warning"] = json!(warning);
+        }
+        
+        if let Some(source) = fn_layer_source(keycode) {
+            response["fn_layer"] = json!({
+                "shortcut": format!("Fn+{}", source.name),
+                "note": format!(
+                    "Keyboards without a {} key (Mac laptops, compact Magic Keyboard) type it as Fn+{}",
+                    keycode.name, source.name
+                ),
+            });
+        } else if let Some(effective) = fn_layer_key(keycode) {
+            response["fn_layer"] = json!({
+                "shortcut": format!("Fn+{}", keycode.name),
+                "key": effective.name,
+                "note": format!("Fn+{} is {} on Mac keyboards", keycode.name, effective.name),
+            });
+        }
+        
+        if let Some(layout) = layout {
+            let available = keycode.available_on(layout);
+            response["available_on_layout"] = json!(available);
+            if !available {
+                response["layout_note"] = json!(format!(
+                    "{} does not exist on {} keyboards (only on: {})",
+                    keycode.name,
+                    layout.to_string().to_uppercase(),
+                    keycode.layouts.iter()
+                        .map(|l| l.to_string().to_uppercase())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ));
             }
+        }
+        
+        // Media keys and F-keys aliased to media functions need system events
+        let media = lookup_media_key(&args.key_name).or_else(|| {
+            if keycode.category == KeyCategory::MediaKeys {
+                media_key_for_code(keycode.code)
+            } else {
+                None
+            }
+        });
+        if let Some(media) = media {
+            response["media"] = media_key_json(media);
+        }
+        
+        Ok(response)
+    } else if let Some(keycode) = KEY_DATABASE.lookup_shifted(args.key_name.trim()) {
+        // Shifted symbols ("{", "?") are a base key pressed with Shift
+        let combination = KeyCombination {
+            modifiers: Modifiers::SHIFT,
+            key: keycode.clone(),
+            shifted_symbol: Some(args.key_name.trim().to_string()),
+            fn_key: None,
+        };
+        
+        let mut response = json!({
+            "found": true,
+            "query_type": "shifted_symbol",
+            "symbol": combination.shifted_symbol,
+            "shortcut": combination.to_string(),
+            "keycodes": combination.to_keycodes(),
+            "modifiers": combination.modifiers.keys().into_iter().map(key_json).collect::<Vec<_>>(),
+            "key": key_json(keycode),
+            "expansion": combination.expansion(),
+            "applescript": combination.to_applescript(),
+        });
+        
+        if let Some(format) = output_format {
+            add_script(&mut response, format, combination.to_script(format));
+        }
+        
+        Ok(response)
+    } else if let Some(media) = lookup_media_key(&args.key_name) {
+        Ok(json!({
+            "found": true,
+            "query_type": "media_key",
+            "media_key": media_key_json(media),
         }))
+    } else if let Ok(code) = args.key_name.trim().parse::<u16>() {
+        // Numeric input that is not a key name ("49") is treated as a key code
+        Ok(reverse_lookup(code))
     } else if args.fuzzy {
         // Try fuzzy search
         let searcher = FuzzySearcher::new();
@@ -144,11 +496,9 @@ pub async fn handle_lookup(args: Value) -> Result<Value> {
         Ok(json!({
             "found": false,
             "suggestions": results.into_iter().map(|(key, score)| {
-                json!({
-                    "name": key.name,
-                    "code": key.code,
-                    "score": score,
-                })
+                let mut suggestion = key_json(key);
+                suggestion["score"] = json!(score);
+                suggestion
             }).collect::<Vec<_>>()
         }))
     } else {
@@ -159,10 +509,108 @@ pub async fn handle_lookup(args: Value) -> Result<Value> {
     }
 }
 
+/// Full description of a key: code, category, aliases, layouts, Carbon
+/// constant, glyph, HID usage, description and printable flag
+pub(crate) fn key_json(key: &KeyCode) -> Value {
+    serde_json::to_value(key).unwrap_or_else(|_| json!({ "name": key.name, "code": key.code }))
+}
+
+/// Describe a media key and how to trigger it
+fn media_key_json(media: &MediaKey) -> Value {
+    let note = match media.key_code.filter(|_| media.triggerable_by_key_code) {
+        Some(code) => format!("AppleScript 'key code {}' triggers {}; the alternatives also work.", code, media.name),
+        None => "Media keys are system-defined events; AppleScript 'key code' cannot trigger them. \
+            Use one of the alternatives.".to_string(),
+    };
+    
+    json!({
+        "name": media.name,
+        "nx_keytype": media.nx_keytype,
+        "nx_constant": media.nx_constant,
+        "key_code": media.key_code,
+        "triggerable_by_key_code": media.triggerable_by_key_code,
+        "description": media.description,
+        "note": note,
+        "alternatives": media.alternatives().into_iter().map(|alt| {
+            json!({
+                "language": alt.language,
+                "description": alt.description,
+                "code": alt.code,
+            })
+        }).collect::<Vec<_>>(),
+    })
+}
+
+/// The media key behind `key` when AppleScript `key code` cannot trigger it
+fn untriggerable_media_key(key: &KeyCode) -> Option<&'static MediaKey> {
+    if key.category != KeyCategory::MediaKeys {
+        return None;
+    }
+    media_key_for_code(key.code).filter(|media| !media.triggerable_by_key_code)
+}
+
+/// Replace a response's `key code` scripts with a pointer to the media key's alternatives
+fn withhold_applescript(response: &mut Value, media: &MediaKey) {
+    if let Some(fields) = response.as_object_mut() {
+        fields.remove("applescript");
+        fields.remove("applescript_list");
+    }
+    response["applescript_note"] = json!(format!(
+        "AppleScript 'key code' cannot trigger the media key {}; use one of the alternatives under 'media'",
+        media.name
+    ));
+    response["media"] = media_key_json(media);
+}
+
+#[derive(Deserialize)]
+struct ReverseLookupArgs {
+    code: u16,
+}
+
+/// Handle reverse_lookup_keycode tool call
+pub async fn handle_reverse_lookup(args: Value) -> Result<Value> {
+    let args: ReverseLookupArgs = serde_json::from_value(args)?;
+    debug!("Reverse lookup for code: {}", args.code);
+    
+    Ok(reverse_lookup(args.code))
+}
+
+/// Build the response listing all keys that share `code`
+fn reverse_lookup(code: u16) -> Value {
+    let keys = KEY_DATABASE.lookup_all_by_code(code);
+    
+    if keys.is_empty() {
+        return json!({
+            "found": false,
+            "query_type": "keycode",
+            "code": code,
+            "message": format!("No key uses code {}", code),
+        });
+    }
+    
+    let mut response = json!({
+        "found": true,
+        "query_type": "keycode",
+        "code": code,
+        "canonical": keys[0].name,
+        "applescript": KeyCombination::from(keys[0]).to_applescript(),
+        "keys": keys.iter().enumerate().map(|(i, key)| {
+            let mut entry = key_json(key);
+            entry["canonical"] = json!(i == 0);
+            entry
+        }).collect::<Vec<_>>()
+    });
+    if let Some(media) = untriggerable_media_key(keys[0]) {
+        withhold_applescript(&mut response, media);
+    }
+    response
+}
+
 #[derive(Deserialize)]
 struct SearchArgs {
     query: Option<String>,
     category: Option<String>,
+    layout: Option<String>,
 }
 
 /// Handle search_keys tool call
@@ -170,6 +618,11 @@ pub async fn handle_search(args: Value) -> Result<Value> {
     let args: SearchArgs = serde_json::from_value(args)?;
     debug!("Searching keys: query={:?}, category={:?}", args.query, args.category);
     
+    let layout = match parse_layout(args.layout.as_deref()) {
+        Ok(layout) => layout,
+        Err(response) => return Ok(response),
+    };
+    
     let mut results = Vec::new();
     
     if let Some(category_str) = args.category {
@@ -191,7 +644,8 @@ pub async fn handle_search(args: Value) -> Result<Value> {
             return Ok(json!({
                 "error": format!("Invalid category: {}", category_str),
                 "valid_categories": ["letters", "numbers", "function_keys", "modifier_keys", 
-                                   "navigation_keys", "special_keys", "numpad_keys", "punctuation"]
+                                   "navigation_keys", "special_keys", "numpad_keys", "punctuation",
+                                   "media_keys"]
             }));
         }
     } else if let Some(query) = args.query {
@@ -199,24 +653,28 @@ pub async fn handle_search(args: Value) -> Result<Value> {
         results = KEY_DATABASE.search(&query, true);
     } else {
         // No filters - return all keys
-        results = KEY_DATABASE.all_keys();
+        results = KEY_DATABASE.all_keys().iter().collect();
+    }
+    
+    if let Some(layout) = layout {
+        results.retain(|key| key.available_on(layout));
     }
     
     Ok(json!({
         "count": results.len(),
-        "keys": results.into_iter().map(|key| {
-            json!({
-                "name": key.name,
-                "code": key.code,
-                "category": key.category.to_string(),
-            })
-        }).collect::<Vec<_>>()
+        "keys": results.into_iter().map(key_json).collect::<Vec<_>>()
     }))
 }
 
 #[derive(Deserialize)]
 struct CombinationArgs {
     shortcut: String,
+    notation: Option<String>,
+    style: Option<String>,
+    delay: Option<f64>,
+    output_format: Option<String>,
+    #[serde(default)]
+    bindings: bool,
 }
 
 /// Handle get_key_combinations tool call
@@ -224,34 +682,396 @@ pub async fn handle_combinations(args: Value) -> Result<Value> {
     let args: CombinationArgs = serde_json::from_value(args)?;
     debug!("Parsing shortcut: {}", args.shortcut);
     
-    match parse_shortcut(&args.shortcut) {
-        Ok(combination) => {
-            Ok(json!({
-                "success": true,
-                "shortcut": combination.to_string(),
-                "keycodes": combination.to_keycodes(),
-                "modifiers": combination.modifiers.iter().map(|m| {
-                    json!({
-                        "name": m.name,
-                        "code": m.code,
-                    })
-                }).collect::<Vec<_>>(),
-                "key": {
-                    "name": combination.key.name,
-                    "code": combination.key.code,
+    if let Some(response) = invalid_delay(args.delay) {
+        return Ok(response);
+    }
+    
+    let notation = match parse_notation(args.notation.as_deref()) {
+        Ok(notation) => notation,
+        Err(response) => return Ok(response),
+    };
+    let style = match parse_style(args.style.as_deref()) {
+        Ok(style) => style,
+        Err(response) => return Ok(response),
+    };
+    let output_format = match parse_output_format(args.output_format.as_deref()) {
+        Ok(format) => format,
+        Err(response) => return Ok(response),
+    };
+    
+    // Modifier-only gestures ("double-tap Control", "hold Option") are native syntax
+    if matches!(notation, None | Some(Notation::Native)) && is_gesture(&args.shortcut) {
+        return Ok(match parse_gesture(&args.shortcut) {
+            Ok(_) if output_format.is_some() || style.is_some() || args.bindings => json!({
+                "success": false,
+                "error": "output_format, style and bindings are not supported for gestures",
+                "hint": "Omit them; the response's applescript presses the gesture",
+            }),
+            Ok(gesture) => {
+                let mut response = gesture_json(&gesture);
+                response["success"] = json!(true);
+                response["notation"] = json!(Notation::Native);
+                response
+            }
+            Err(e) => parse_error_json(&e.with_suggestions(), &args.shortcut, Notation::Native),
+        });
+    }
+    
+    let (notation, mut sequence) = match parse_any_shortcut(&args.shortcut, notation) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            let notation = notation.unwrap_or_else(|| detect_notation(&args.shortcut));
+            return Ok(parse_error_json(&e, &args.shortcut, notation));
+        }
+    };
+    
+    let mut response = if sequence.is_single() {
+        combination_json(&sequence.chords[0])
+    } else {
+        sequence.delay = args.delay;
+        json!({
+            "sequence": true,
+            "shortcut": sequence.to_string(),
+            "keycodes": sequence.to_keycodes(),
+            "chords": sequence.chords.iter().map(combination_json).collect::<Vec<_>>(),
+            "delay": sequence.delay,
+            "applescript": sequence.to_applescript(),
+            "applescript_list": sequence.to_applescript_list(),
+        })
+    };
+    if let Some(media) = sequence.chords.iter().find_map(|chord| untriggerable_media_key(&chord.key)) {
+        withhold_applescript(&mut response, media);
+    }
+    
+    response["success"] = json!(true);
+    response["notation"] = json!(notation);
+    if let Some(style) = style {
+        response["style"] = json!(style);
+        response["formatted"] = json!(sequence.render(style));
+    }
+    if let Some(format) = output_format {
+        add_script(&mut response, format, sequence.to_script(format));
+    }
+    if args.bindings {
+        if sequence.is_single() {
+            response["bindings"] = bindings_json(&sequence.chords[0]);
+        } else {
+            for (chord, combination) in response["chords"].as_array_mut().into_iter().flatten().zip(&sequence.chords) {
+                chord["bindings"] = bindings_json(combination);
+            }
+        }
+    }
+    
+    Ok(response)
+}
+
+/// Error response pointing at the part of `shortcut` that did not parse
+fn parse_error_json(e: &ParseError, shortcut: &str, notation: Notation) -> Value {
+    let hint = match e.suggestions() {
+        [] => "Use format like 'Cmd+A', 'Ctrl+Shift+F5', '⌘⇧A', 'Ctrl+X Ctrl+S' for a sequence, \
+            or 'double-tap Control' or 'hold Option for 2s' for a gesture, \
+            or pass 'notation' for Emacs, Vim, VS Code, Electron or Cocoa shortcuts".to_string(),
+        suggestions => format!("Did you mean {}?", suggestions
+            .iter()
+            .map(|name| format!("'{}'", name))
+            .collect::<Vec<_>>()
+            .join(" or ")),
+    };
+    
+    json!({
+        "success": false,
+        "error": e.to_string(),
+        "notation": notation,
+        "span": e.span().map(|span| json!({ "start": span.start, "end": span.end })),
+        "suggestions": e.suggestions(),
+        "diagnostic": e.diagnostic(shortcut),
+        "hint": hint
+    })
+}
+
+/// Modifiers, timing and script of a modifier-only gesture
+fn gesture_json(gesture: &Gesture) -> Value {
+    json!({
+        "gesture": gesture.kind,
+        "shortcut": gesture.to_string(),
+        "keycodes": gesture.to_keycodes(),
+        "modifiers": gesture.modifiers.keys().into_iter().map(key_json).collect::<Vec<_>>(),
+        "applescript": gesture.to_applescript(),
+    })
+}
+
+/// Key codes and keys of one parsed shortcut
+fn combination_json(combination: &KeyCombination) -> Value {
+    let mut response = json!({
+        "shortcut": combination.to_string(),
+        "keycodes": combination.to_keycodes(),
+        "modifiers": combination.modifiers.keys().into_iter().map(key_json).collect::<Vec<_>>(),
+        "key": key_json(&combination.key),
+        "applescript": combination.to_applescript(),
+    });
+    if let Some(media) = untriggerable_media_key(&combination.key) {
+        withhold_applescript(&mut response, media);
+    }
+    
+    // Explain why Shift appears when a shifted symbol was used, or why Fn is gone
+    if let Some(expansion) = combination.expansion() {
+        if combination.shifted_symbol.is_some() {
+            response["shifted_symbol"] = json!(combination.shifted_symbol);
+        }
+        response["expansion"] = json!(expansion);
+    }
+    if let Some(fn_form) = combination.fn_layer_form() {
+        response["fn_layer_shortcut"] = json!(fn_form);
+    }
+    
+    // System Events has no `using` constant for Fn or Caps Lock
+    let unsupported = combination.applescript_unsupported_modifiers();
+    if !unsupported.is_empty() {
+        response["warning"] = json!(format!(
+            "AppleScript cannot hold {}; the applescript presses the key without them",
+            unsupported.join(" or ")
+        ));
+        response["unsupported_modifiers"] = json!(unsupported);
+    }
+    
+    response
+}
+
+/// Hotkey daemon bindings of one combination, with a placeholder action to replace
+fn bindings_json(combination: &KeyCombination) -> Value {
+    let shortcut = combination.to_string();
+    json!({
+        "hammerspoon": combination.to_hammerspoon_binding(&format!("hs.alert.show(\"{}\")", shortcut)),
+        "skhd": combination.to_skhd(&format!("echo \"{}\"", shortcut)),
+    })
+}
+
+/// Error response for a delay that is not a non-negative number of seconds
+fn invalid_delay(delay: Option<f64>) -> Option<Value> {
+    delay.filter(|d| !d.is_finite() || *d < 0.0).map(|d| json!({
+        "success": false,
+        "error": format!("Invalid delay {}; expected a non-negative number of seconds", d),
+    }))
+}
+
+#[derive(Deserialize)]
+struct NormalizeArgs {
+    shortcuts: Vec<String>,
+    notation: Option<String>,
+}
+
+/// Handle normalize_shortcut tool call
+pub async fn handle_normalize(args: Value) -> Result<Value> {
+    let args: NormalizeArgs = serde_json::from_value(args)?;
+    debug!("Normalizing {} shortcuts", args.shortcuts.len());
+    
+    let notation = match parse_notation(args.notation.as_deref()) {
+        Ok(notation) => notation,
+        Err(response) => return Ok(response),
+    };
+    
+    // Inputs grouped by canonical chords, which sort by key code then modifiers
+    let mut groups: BTreeMap<Vec<KeyCombination>, Vec<&str>> = BTreeMap::new();
+    let mut results = Vec::new();
+    
+    for shortcut in &args.shortcuts {
+        match parse_any_shortcut(shortcut, notation) {
+            Ok((notation, sequence)) => {
+                let canonical = sequence.canonical();
+                results.push(json!({
+                    "input": shortcut,
+                    "success": true,
+                    "notation": notation,
+                    "canonical": canonical.to_string(),
+                    "keycodes": canonical.to_keycodes(),
+                }));
+                groups.entry(canonical.chords).or_default().push(shortcut);
+            }
+            Err(e) => {
+                let e = e.with_suggestions();
+                results.push(json!({
+                    "input": shortcut,
+                    "success": false,
+                    "error": e.to_string(),
+                    "diagnostic": e.diagnostic(shortcut),
+                }));
+            }
+        }
+    }
+    
+    let canonical = |chords: &[KeyCombination]| KeySequence { chords: chords.to_vec(), delay: None }.to_string();
+    
+    Ok(json!({
+        "count": args.shortcuts.len(),
+        "results": results,
+        "unique": groups.keys().map(|chords| canonical(chords)).collect::<Vec<_>>(),
+        "duplicates": groups
+            .iter()
+            .filter(|(_, inputs)| inputs.len() > 1)
+            .map(|(chords, inputs)| json!({ "canonical": canonical(chords), "inputs": inputs }))
+            .collect::<Vec<_>>(),
+    }))
+}
+
+#[derive(Deserialize)]
+struct KarabinerMapping {
+    from: String,
+    to: String,
+}
+
+#[derive(Deserialize)]
+struct KarabinerArgs {
+    mappings: Vec<KarabinerMapping>,
+    description: Option<String>,
+    notation: Option<String>,
+}
+
+/// Handle karabiner_rule tool call
+pub async fn handle_karabiner_rule(args: Value) -> Result<Value> {
+    let args: KarabinerArgs = serde_json::from_value(args)?;
+    debug!("Building Karabiner rule from {} mappings", args.mappings.len());
+    
+    let notation = match parse_notation(args.notation.as_deref()) {
+        Ok(notation) => notation,
+        Err(response) => return Ok(response),
+    };
+    
+    if args.mappings.is_empty() {
+        return Ok(json!({
+            "success": false,
+            "error": "No mappings given; pass at least one {\"from\", \"to\"} pair",
+        }));
+    }
+    
+    let mut manipulators = Vec::new();
+    let mut summaries = Vec::new();
+    
+    for (index, mapping) in args.mappings.iter().enumerate() {
+        let mut sequences = Vec::new();
+        for shortcut in [&mapping.from, &mapping.to] {
+            match parse_any_shortcut(shortcut, notation) {
+                Ok((_, sequence)) => sequences.push(sequence),
+                Err(e) => {
+                    let mut response = parse_error_json(&e, shortcut, notation.unwrap_or_else(|| detect_notation(shortcut)));
+                    response["mapping"] = json!(index);
+                    return Ok(response);
                 }
-            }))
+            }
         }
-        Err(e) => {
-            Ok(json!({
-                "success": false,
-                "error": e.to_string(),
-                "hint": "Use format like 'Cmd+A', 'Ctrl+Shift+F5', or '⌘⇧A'"
-            }))
+        let (from, to) = (&sequences[0], &sequences[1]);
+        
+        match KarabinerManipulator::new(from, to) {
+            Ok(manipulator) => manipulators.push(manipulator),
+            Err(e) => {
+                return Ok(json!({
+                    "success": false,
+                    "error": e.to_string(),
+                    "mapping": index,
+                }));
+            }
+        }
+        summaries.push(format!("{} to {}", from, to));
+    }
+    
+    let rule = KarabinerRule {
+        description: args.description.unwrap_or_else(|| format!("Remap {}", summaries.join(", "))),
+        manipulators,
+    };
+    
+    Ok(json!({
+        "success": true,
+        "rule": rule,
+        "complex_modifications": {
+            "title": rule.description,
+            "rules": [rule],
+        },
+        "hint": "Add 'rule' to complex_modifications.rules in ~/.config/karabiner/karabiner.json, \
+            or save 'complex_modifications' to ~/.config/karabiner/assets/complex_modifications/ and enable it in Karabiner-Elements"
+    }))
+}
+
+#[derive(Deserialize)]
+struct CharArgs {
+    character: String,
+}
+
+/// Handle char_to_keystroke tool call
+pub async fn handle_char_to_keystroke(args: Value) -> Result<Value> {
+    let args: CharArgs = serde_json::from_value(args)?;
+    debug!("Mapping character: {:?}", args.character);
+    
+    let mut chars = args.character.chars();
+    let ch = match (chars.next(), chars.next()) {
+        (Some(ch), None) => ch,
+        _ => {
+            return Ok(json!({
+                "found": false,
+                "error": format!("Expected exactly one character, got {:?}", args.character),
+            }));
         }
+    };
+    
+    match char_to_keystroke(ch) {
+        Some(combination) => Ok(json!({
+            "found": true,
+            "character": ch.to_string(),
+            "layout": "US",
+            "shift_required": combination.modifiers.intersects(Modifiers::ANY_SHIFT),
+            "shortcut": combination.to_string(),
+            "keycodes": combination.to_keycodes(),
+            "modifiers": combination.modifiers.keys().into_iter().map(key_json).collect::<Vec<_>>(),
+            "key": key_json(&combination.key),
+            "expansion": combination.expansion(),
+            "applescript": combination.to_applescript(),
+        })),
+        None => Ok(json!({
+            "found": false,
+            "character": ch.to_string(),
+            "message": format!(
+                "{:?} (U+{:04X}) cannot be typed with a single keystroke on the US layout; use 'keystroke' instead",
+                ch, ch as u32
+            ),
+        })),
     }
 }
 
+#[derive(Deserialize)]
+struct TextArgs {
+    text: String,
+    delay: Option<f64>,
+}
+
+/// Handle text_to_keycodes tool call
+pub async fn handle_text_to_keycodes(args: Value) -> Result<Value> {
+    let args: TextArgs = serde_json::from_value(args)?;
+    debug!("Converting text of {} chars", args.text.chars().count());
+    
+    if let Some(response) = invalid_delay(args.delay) {
+        return Ok(response);
+    }
+    
+    let plan = text_to_keystrokes(&args.text);
+    
+    Ok(json!({
+        "success": true,
+        "complete": plan.is_complete(),
+        "layout": "US",
+        "events": plan.events.iter().map(|event| json!({
+            "character": event.character.to_string(),
+            "key": event.combination.key.name,
+            "code": event.combination.key.code,
+            "shift": event.combination.modifiers.intersects(Modifiers::ANY_SHIFT),
+            "keycodes": event.combination.to_keycodes(),
+        })).collect::<Vec<_>>(),
+        "untypeable": plan.untypeable.iter().map(|skipped| json!({
+            "character": skipped.character.to_string(),
+            "index": skipped.index,
+            "codepoint": format!("U+{:04X}", skipped.character as u32),
+        })).collect::<Vec<_>>(),
+        "applescript": plan.to_applescript(args.delay),
+    }))
+}
+
 /// Handle list_categories tool call
 pub async fn handle_categories(_args: Value) -> Result<Value> {
     let categories = KEY_DATABASE.categories();
@@ -270,6 +1090,7 @@ pub async fn handle_categories(_args: Value) -> Result<Value> {
                     KeyCategory::SpecialKeys => "Special keys (space, tab, return, delete, escape)",
                     KeyCategory::NumpadKeys => "Numeric keypad keys",
                     KeyCategory::Punctuation => "Punctuation and symbol keys",
+                    KeyCategory::MediaKeys => "Media and system keys (volume, brightness, playback)",
                 }
             })
         }).collect::<Vec<_>>()
@@ -6,22 +6,34 @@ use rmcp::{
     service::RequestContext,
     tool, tool_handler, tool_router,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 
-use crate::keycode::{KEY_DATABASE, KeyCategory, parse_shortcut};
-use crate::search::FuzzySearcher;
+use crate::keycode::KEY_DATABASE;
+use crate::mcp::tools::{self, key_json};
 
-#[derive(Debug, Deserialize, schemars::JsonSchema)]
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
 pub struct LookupKeyCodeRequest {
-    /// Name of the key (e.g., 'A', 'Space', 'Command', 'F1')
+    /// Name of the key (e.g., 'A', 'Space', 'F1', 'F3+' for F3's secondary function), or a numeric key code like '49' for a reverse lookup
     pub key_name: String,
     /// Enable fuzzy matching for typos
     #[serde(default)]
     pub fuzzy: bool,
+    /// Physical keyboard layout to check the key against (ansi, iso, jis)
+    #[serde(default)]
+    pub layout: Option<String>,
+    /// Also return a script pressing the key in this language (applescript, jxa, swift, python, pyautogui, hammerspoon)
+    #[serde(default)]
+    pub output_format: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct ReverseLookupKeyCodeRequest {
+    /// AppleScript key code (e.g. 49 for Space)
+    pub code: u16,
 }
 
-#[derive(Debug, Deserialize, schemars::JsonSchema)]
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
 pub struct SearchKeysRequest {
     /// Search term (partial match supported)
     #[serde(default)]
@@ -29,12 +41,88 @@ pub struct SearchKeysRequest {
     /// Filter by category (letters, numbers, function_keys, etc.)
     #[serde(default)]
     pub category: Option<String>,
+    /// Only return keys present on this physical layout (ansi, iso, jis)
+    #[serde(default)]
+    pub layout: Option<String>,
 }
 
-#[derive(Debug, Deserialize, schemars::JsonSchema)]
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
 pub struct GetKeyCombinationRequest {
-    /// Shortcut string (e.g., 'Cmd+A', 'Ctrl+Shift+F5', '⌘⇧A')
+    /// Shortcut string (e.g., 'Cmd+A', 'Ctrl+Shift+F5', '⌘⇧A'), space-separated chords (e.g., 'Cmd+K Cmd+S'), or a modifier-only gesture (e.g., 'Fn', 'double-tap Control')
     pub shortcut: String,
+    /// Notation (native, emacs, vim, vscode, electron, cocoa); detected when omitted
+    #[serde(default)]
+    pub notation: Option<String>,
+    /// Also render the shortcut in this style (e.g. 'glyphs', 'html', 'spoken')
+    #[serde(default)]
+    pub style: Option<String>,
+    /// Optional pause in seconds between chords
+    #[serde(default)]
+    pub delay: Option<f64>,
+    /// Also return a script pressing the shortcut in this language
+    #[serde(default)]
+    pub output_format: Option<String>,
+    /// Also return Hammerspoon and skhd hotkey bindings for each chord
+    #[serde(default)]
+    pub bindings: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct CharToKeystrokeRequest {
+    /// A single character (e.g. '?', 'A', '@')
+    pub character: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct TextToKeycodesRequest {
+    /// Text to type; newlines become Return and tabs become Tab
+    pub text: String,
+    /// Optional pause in seconds after each key press
+    #[serde(default)]
+    pub delay: Option<f64>,
+}
+
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct NormalizeShortcutRequest {
+    /// Shortcuts to normalize, in any supported notation
+    pub shortcuts: Vec<String>,
+    /// Notation of every shortcut; detected per shortcut when omitted
+    #[serde(default)]
+    pub notation: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct KarabinerMappingRequest {
+    /// Shortcut to remap, a single chord (e.g. 'Cmd+Shift+A')
+    pub from: String,
+    /// Shortcut to send instead; a sequence like 'Ctrl+X Ctrl+S' sends each chord in turn
+    pub to: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct KarabinerRuleRequest {
+    /// Shortcut pairs, one manipulator each
+    pub mappings: Vec<KarabinerMappingRequest>,
+    /// Rule description shown in Karabiner-Elements; built from the mappings when omitted
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Notation of every shortcut; detected per shortcut when omitted
+    #[serde(default)]
+    pub notation: Option<String>,
+}
+
+/// Pass a request to the shared tool handlers as JSON arguments
+fn arguments<T: Serialize>(request: &T) -> Value {
+    serde_json::to_value(request).unwrap()
+}
+
+/// Wrap a shared tool handler's response as an rmcp tool result
+fn tool_result(response: anyhow::Result<Value>) -> Result<CallToolResult, McpError> {
+    let response = response.map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+    
+    Ok(CallToolResult::success(vec![Content::text(
+        serde_json::to_string_pretty(&response).unwrap()
+    )]))
 }
 
 #[derive(Clone)]
@@ -42,6 +130,8 @@ pub struct MacKeyboardServer {
     tool_router: ToolRouter<MacKeyboardServer>,
 }
 
+// Every tool delegates to the handlers in `crate::mcp::tools`, so this server
+// returns the same responses as the hand-rolled one in `crate::mcp::server`.
 #[tool_router]
 impl MacKeyboardServer {
     pub fn new() -> Self {
@@ -52,189 +142,82 @@ impl MacKeyboardServer {
             tool_router: Self::tool_router(),
         }
     }
-
-    #[tool(description = "Find AppleScript key code for a specific key")]
-    fn lookup_keycode(
+    
+    #[tool(description = "Find AppleScript key code for a specific key. Use 'F{n}+' format (e.g., 'F3+') to get secondary function info for F-keys")]
+    async fn lookup_keycode(
         &self,
-        Parameters(LookupKeyCodeRequest { key_name, fuzzy }): Parameters<LookupKeyCodeRequest>,
+        Parameters(request): Parameters<LookupKeyCodeRequest>,
     ) -> Result<CallToolResult, McpError> {
-        tracing::debug!("Looking up key: {}", key_name);
-        
-        if let Some(keycode) = KEY_DATABASE.lookup(&key_name) {
-            let result = json!({
-                "found": true,
-                "key": {
-                    "name": keycode.name,
-                    "code": keycode.code,
-                    "category": keycode.category.to_string(),
-                    "aliases": keycode.aliases,
-                }
-            });
-            
-            Ok(CallToolResult::success(vec![Content::text(
-                serde_json::to_string_pretty(&result).unwrap()
-            )]))
-        } else if fuzzy {
-            // Try fuzzy search
-            let searcher = FuzzySearcher::new();
-            let results = searcher.search_keys(&key_name, 5);
-            
-            let suggestions: Vec<Value> = results.into_iter().map(|(key, score)| {
-                json!({
-                    "name": key.name,
-                    "code": key.code,
-                    "score": score,
-                })
-            }).collect();
-            
-            let result = json!({
-                "found": false,
-                "suggestions": suggestions,
-            });
-            
-            Ok(CallToolResult::success(vec![Content::text(
-                serde_json::to_string_pretty(&result).unwrap()
-            )]))
-        } else {
-            let result = json!({
-                "found": false,
-                "message": format!("Key '{}' not found. Try enabling fuzzy search.", key_name),
-            });
-            
-            Ok(CallToolResult::success(vec![Content::text(
-                serde_json::to_string_pretty(&result).unwrap()
-            )]))
-        }
+        tracing::debug!("Looking up key: {}", request.key_name);
+        tool_result(tools::handle_lookup(arguments(&request)).await)
     }
-
+    
+    #[tool(description = "Find every key that uses a given AppleScript key code (e.g. to decode 'key code 56' in an existing script)")]
+    async fn reverse_lookup_keycode(
+        &self,
+        Parameters(request): Parameters<ReverseLookupKeyCodeRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        tracing::debug!("Reverse lookup for code: {}", request.code);
+        tool_result(tools::handle_reverse_lookup(arguments(&request)).await)
+    }
+    
     #[tool(description = "Search for keys by pattern or category")]
-    fn search_keys(
+    async fn search_keys(
         &self,
-        Parameters(SearchKeysRequest { query, category }): Parameters<SearchKeysRequest>,
+        Parameters(request): Parameters<SearchKeysRequest>,
     ) -> Result<CallToolResult, McpError> {
-        tracing::debug!("Searching keys: query={:?}, category={:?}", query, category);
-        
-        let mut results = Vec::new();
-        
-        if let Some(category_str) = category {
-            // Search by category
-            if let Ok(cat) = category_str.parse::<KeyCategory>() {
-                let keys = KEY_DATABASE.by_category(cat);
-                for key in keys {
-                    if let Some(q) = &query {
-                        // Filter by query within category
-                        if key.name.to_lowercase().contains(&q.to_lowercase()) {
-                            results.push(key);
-                        }
-                    } else {
-                        // All keys in category
-                        results.push(key);
-                    }
-                }
-            } else {
-                let error = json!({
-                    "error": format!("Invalid category: {}", category_str),
-                    "valid_categories": ["letters", "numbers", "function_keys", "modifier_keys", 
-                                       "navigation_keys", "special_keys", "numpad_keys", "punctuation"]
-                });
-                
-                return Ok(CallToolResult::success(vec![Content::text(
-                    serde_json::to_string_pretty(&error).unwrap()
-                )]));
-            }
-        } else if let Some(q) = query {
-            // Search all keys by query
-            results = KEY_DATABASE.search(&q, true);
-        } else {
-            // No filters - return all keys
-            results = KEY_DATABASE.all_keys();
-        }
-        
-        let response = json!({
-            "count": results.len(),
-            "keys": results.into_iter().map(|key| {
-                json!({
-                    "name": key.name,
-                    "code": key.code,
-                    "category": key.category.to_string(),
-                })
-            }).collect::<Vec<_>>()
-        });
-        
-        Ok(CallToolResult::success(vec![Content::text(
-            serde_json::to_string_pretty(&response).unwrap()
-        )]))
+        tracing::debug!("Searching keys: query={:?}, category={:?}", request.query, request.category);
+        tool_result(tools::handle_search(arguments(&request)).await)
     }
-
-    #[tool(description = "Generate key code sequences for shortcuts")]
-    fn get_key_combinations(
+    
+    #[tool(description = "Generate key code sequences for shortcuts, including multi-chord bindings like 'Ctrl+X Ctrl+S' and Emacs, Vim, VS Code, Electron or Cocoa notation")]
+    async fn get_key_combinations(
         &self,
-        Parameters(GetKeyCombinationRequest { shortcut }): Parameters<GetKeyCombinationRequest>,
+        Parameters(request): Parameters<GetKeyCombinationRequest>,
     ) -> Result<CallToolResult, McpError> {
-        tracing::debug!("Parsing shortcut: {}", shortcut);
-        
-        match parse_shortcut(&shortcut) {
-            Ok(combination) => {
-                let response = json!({
-                    "success": true,
-                    "shortcut": combination.to_string(),
-                    "keycodes": combination.to_keycodes(),
-                    "modifiers": combination.modifiers.iter().map(|m| {
-                        json!({
-                            "name": m.name,
-                            "code": m.code,
-                        })
-                    }).collect::<Vec<_>>(),
-                    "key": {
-                        "name": combination.key.name,
-                        "code": combination.key.code,
-                    }
-                });
-                
-                Ok(CallToolResult::success(vec![Content::text(
-                    serde_json::to_string_pretty(&response).unwrap()
-                )]))
-            }
-            Err(e) => {
-                let response = json!({
-                    "success": false,
-                    "error": e.to_string(),
-                    "hint": "Use format like 'Cmd+A', 'Ctrl+Shift+F5', or '⌘⇧A'"
-                });
-                
-                Ok(CallToolResult::success(vec![Content::text(
-                    serde_json::to_string_pretty(&response).unwrap()
-                )]))
-            }
-        }
+        tracing::debug!("Parsing shortcut: {}", request.shortcut);
+        tool_result(tools::handle_combinations(arguments(&request)).await)
     }
-
+    
+    #[tool(description = "Find the key and modifiers that type a character on the US layout (e.g. '?' is Shift+Slash)")]
+    async fn char_to_keystroke(
+        &self,
+        Parameters(request): Parameters<CharToKeystrokeRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        tracing::debug!("Mapping character: {:?}", request.character);
+        tool_result(tools::handle_char_to_keystroke(arguments(&request)).await)
+    }
+    
+    #[tool(description = "Convert text into the ordered key presses that type it on the US layout, with an AppleScript that replays them")]
+    async fn text_to_keycodes(
+        &self,
+        Parameters(request): Parameters<TextToKeycodesRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        tracing::debug!("Converting text of {} chars", request.text.chars().count());
+        tool_result(tools::handle_text_to_keycodes(arguments(&request)).await)
+    }
+    
+    #[tool(description = "Reduce shortcuts to a canonical form so they can be compared and deduplicated (e.g. 'Shift+Cmd+A', '⌘⇧A' and 'cmd+shift+a' are all 'Command+Shift+A')")]
+    async fn normalize_shortcut(
+        &self,
+        Parameters(request): Parameters<NormalizeShortcutRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        tracing::debug!("Normalizing {} shortcuts", request.shortcuts.len());
+        tool_result(tools::handle_normalize(arguments(&request)).await)
+    }
+    
+    #[tool(description = "Build a Karabiner-Elements complex_modifications rule that remaps shortcuts (e.g. 'Cmd+Shift+A' to 'Ctrl+B')")]
+    async fn karabiner_rule(
+        &self,
+        Parameters(request): Parameters<KarabinerRuleRequest>,
+    ) -> Result<CallToolResult, McpError> {
+        tracing::debug!("Building Karabiner rule from {} mappings", request.mappings.len());
+        tool_result(tools::handle_karabiner_rule(arguments(&request)).await)
+    }
+    
     #[tool(description = "List all available key categories")]
-    fn list_categories(&self) -> Result<CallToolResult, McpError> {
-        let categories = KEY_DATABASE.categories();
-        
-        let response = json!({
-            "categories": categories.into_iter().map(|(cat, count)| {
-                json!({
-                    "name": cat.to_string(),
-                    "count": count,
-                    "description": match cat {
-                        KeyCategory::Letters => "Letter keys A-Z",
-                        KeyCategory::Numbers => "Number keys 0-9",
-                        KeyCategory::FunctionKeys => "Function keys F1-F20",
-                        KeyCategory::ModifierKeys => "Modifier keys (Command, Shift, Option, Control)",
-                        KeyCategory::NavigationKeys => "Navigation keys (arrows, page up/down, home, end)",
-                        KeyCategory::SpecialKeys => "Special keys (space, tab, return, delete, escape)",
-                        KeyCategory::NumpadKeys => "Numeric keypad keys",
-                        KeyCategory::Punctuation => "Punctuation and symbol keys",
-                    }
-                })
-            }).collect::<Vec<_>>()
-        });
-        
-        Ok(CallToolResult::success(vec![Content::text(
-            serde_json::to_string_pretty(&response).unwrap()
-        )]))
+    async fn list_categories(&self) -> Result<CallToolResult, McpError> {
+        tool_result(tools::handle_categories(json!({})).await)
     }
     
     fn _create_resource(&self, uri: &str, name: &str, _description: &str) -> Resource {
@@ -259,13 +242,15 @@ impl ServerHandler for MacKeyboardServer {
             },
             instructions: Some(
                 "This server provides AppleScript key codes for macOS automation. \
-                Use 'lookup_keycode' to find specific keys, 'search_keys' to browse categories, \
-                'get_key_combinations' to parse shortcuts like 'Cmd+A', and 'list_categories' \
-                to see all available key categories.".to_string()
+                Use 'lookup_keycode' to find specific keys, 'reverse_lookup_keycode' to decode a key code, \
+                'search_keys' to browse categories, 'get_key_combinations' to parse shortcuts like 'Cmd+A', \
+                'char_to_keystroke' and 'text_to_keycodes' to type characters and text, \
+                'normalize_shortcut' to compare shortcuts, 'karabiner_rule' to build remapping rules, \
+                and 'list_categories' to see all available key categories.".to_string()
             ),
         }
     }
-
+    
     async fn list_resources(
         &self,
         _request: Option<PaginatedRequestParam>,
@@ -287,7 +272,7 @@ impl ServerHandler for MacKeyboardServer {
             next_cursor: None,
         })
     }
-
+    
     async fn read_resource(
         &self,
         ReadResourceRequestParam { uri }: ReadResourceRequestParam,
@@ -302,11 +287,7 @@ impl ServerHandler for MacKeyboardServer {
                     by_category
                         .entry(key.category.to_string())
                         .or_insert_with(Vec::new)
-                        .push(json!({
-                            "name": key.name,
-                            "code": key.code,
-                            "aliases": key.aliases,
-                        }));
+                        .push(key_json(key));
                 }
                 
                 let content = serde_json::to_string_pretty(&json!({
@@ -349,7 +330,7 @@ impl ServerHandler for MacKeyboardServer {
             )),
         }
     }
-
+    
     async fn initialize(
         &self,
         _request: InitializeRequestParam,
@@ -358,7 +339,7 @@ impl ServerHandler for MacKeyboardServer {
         tracing::info!("Client initialized");
         Ok(self.get_info())
     }
-
+    
     async fn list_prompts(
         &self,
         _request: Option<PaginatedRequestParam>,
@@ -370,7 +351,7 @@ impl ServerHandler for MacKeyboardServer {
             prompts: vec![],
         })
     }
-
+    
     async fn get_prompt(
         &self,
         GetPromptRequestParam { name, .. }: GetPromptRequestParam,
@@ -381,7 +362,7 @@ impl ServerHandler for MacKeyboardServer {
             Some(json!({ "prompt": name }))
         ))
     }
-
+    
     async fn list_resource_templates(
         &self,
         _request: Option<PaginatedRequestParam>,
@@ -22,11 +22,11 @@ impl FuzzySearcher {
         // Search through all keys
         for key in KEY_DATABASE.all_keys() {
             // Check main name
-            if let Some(score) = self.matcher.fuzzy_match(&key.name, query) {
+            if let Some(score) = self.matcher.fuzzy_match(key.name, query) {
                 results.push((key, score));
             } else {
                 // Check aliases
-                for alias in &key.aliases {
+                for alias in key.aliases {
                     if let Some(score) = self.matcher.fuzzy_match(alias, query) {
                         results.push((key, score));
                         break; // Only count once per key
@@ -43,6 +43,37 @@ impl FuzzySearcher {
         results
     }
     
+    /// Suggest keys a mistyped name was probably meant to be, best first
+    ///
+    /// Unlike `search_keys`, this also matches when a name is contained in the
+    /// query, so extra or repeated letters ("Commmand") still find the key.
+    pub fn suggest(&self, query: &str, limit: usize) -> Vec<&'static KeyCode> {
+        let mut results: Vec<(&'static KeyCode, i64)> = Vec::new();
+        
+        for key in KEY_DATABASE.all_keys() {
+            let best = std::iter::once(key.name)
+                .chain(key.aliases.iter().copied())
+                .filter_map(|name| {
+                    let forward = self.matcher.fuzzy_match(name, query);
+                    // Very short names are contained in almost any query
+                    let backward = if name.chars().count() >= 3 {
+                        self.matcher.fuzzy_match(query, name)
+                    } else {
+                        None
+                    };
+                    forward.max(backward)
+                })
+                .max();
+            
+            if let Some(score) = best {
+                results.push((key, score));
+            }
+        }
+        
+        results.sort_by_key(|r| std::cmp::Reverse(r.1));
+        results.into_iter().take(limit).map(|(key, _)| key).collect()
+    }
+    
     /// Calculate similarity between two strings (0.0 to 1.0)
     pub fn calculate_similarity(&self, a: &str, b: &str) -> f64 {
         // Try both directions for better matching
@@ -95,6 +126,17 @@ mod tests {
         assert_eq!(results[0].0.name, "Command");
     }
     
+    #[test]
+    fn test_suggest() {
+        let searcher = FuzzySearcher::new();
+        
+        let names: Vec<_> = searcher.suggest("Comand", 3).iter().map(|k| k.name).collect();
+        assert_eq!(names.first(), Some(&"Command"));
+        
+        let names: Vec<_> = searcher.suggest("Escapee", 3).iter().map(|k| k.name).collect();
+        assert!(names.contains(&"Escape"));
+    }
+    
     #[test]
     fn test_similarity_calculation() {
         let searcher = FuzzySearcher::new();